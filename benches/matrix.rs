@@ -1,14 +1,21 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use ray_tracer_rs::matrix::Matrix4x4;
+use ray_tracer_rs::{
+    matrix::Matrix4x4,
+    tuple::{Elem, Tuple4},
+};
 
-fn matrix_4x4_inverse(data: &[f64; 16]) {
+fn matrix_4x4_inverse(data: &[Elem; 16]) {
     Matrix4x4::new(*data).inverse();
 }
 
-fn matrix_4x4_det(data: &[f64; 16]) {
+fn matrix_4x4_det(data: &[Elem; 16]) {
     Matrix4x4::new(*data).det();
 }
 
+fn matrix_4x4_inverse_affine(data: &[Elem; 16]) {
+    Matrix4x4::new(*data).inverse_affine();
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("Matrix4x4 inverse", |b| {
         b.iter(|| {
@@ -31,5 +38,34 @@ fn matrix_det(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark, matrix_det);
+fn matrix_inverse_affine(c: &mut Criterion) {
+    c.bench_function("Matrix4x4 inverse_affine", |b| {
+        b.iter(|| {
+            matrix_4x4_inverse_affine(black_box(&[
+                2.0, 0.5, 0.0, 10.0, 0.0, 3.0, 1.0, 5.0, 1.0, 0.0, 4.0, 7.0, 0.0, 0.0, 0.0, 1.0,
+            ]))
+        })
+    });
+}
+
+fn matrix_mul(c: &mut Criterion) {
+    let a = Matrix4x4::translation(10.0, 5.0, 7.0) * Matrix4x4::rotation_x(0.5);
+    let b = Matrix4x4::scaling(5.0, 2.0, 3.0) * Matrix4x4::rotation_y(1.5);
+    let t = Tuple4::point(1.0, -2.0, 3.0);
+
+    c.bench_function("Matrix4x4 mul", |bench| {
+        bench.iter(|| black_box(&a) * black_box(&b))
+    });
+    c.bench_function("Matrix4x4 mul Tuple4", |bench| {
+        bench.iter(|| black_box(&a) * black_box(t))
+    });
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    matrix_det,
+    matrix_inverse_affine,
+    matrix_mul
+);
 criterion_main!(benches);
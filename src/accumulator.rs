@@ -0,0 +1,146 @@
+use crate::canvas::Canvas;
+
+/// Blends successive frames of a static-camera animation with an
+/// exponential moving average.
+///
+/// Until `1 / frames` drops below `alpha` the blend is a plain running
+/// mean, so the first few frames converge quickly instead of being
+/// dominated by the very first one.
+pub struct TemporalAccumulator {
+    alpha: f64,
+    frames: usize,
+    canvas: Canvas,
+}
+
+impl TemporalAccumulator {
+    pub fn new(width: usize, height: usize, alpha: f64) -> Self {
+        assert!(
+            alpha > 0.0 && alpha <= 1.0,
+            "Blend factor must be in (0, 1]"
+        );
+
+        TemporalAccumulator {
+            alpha,
+            frames: 0,
+            canvas: Canvas::new(width, height),
+        }
+    }
+
+    pub fn accumulate(&mut self, frame: &Canvas) {
+        assert!(
+            frame.width() == self.canvas.width() && frame.height() == self.canvas.height(),
+            "Frame size doesn't match the accumulator"
+        );
+
+        self.frames += 1;
+        let weight = self.alpha.max(1.0 / self.frames as f64);
+
+        for y in 0..frame.height() {
+            for x in 0..frame.width() {
+                let previous = self.canvas.get_pixel((x, y));
+                let current = frame.get_pixel((x, y));
+                let blended = previous * (1.0 - weight) + current * weight;
+                self.canvas.put_pixel(blended, (x, y));
+            }
+        }
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    pub fn reset(&mut self) {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        self.frames = 0;
+        self.canvas = Canvas::new(width, height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::sampling::{Sampler, Stratified};
+
+    fn noisy_frame(frame: u64) -> Canvas {
+        let sampler = Stratified::for_frame(7, frame, 1);
+        let mut canvas = Canvas::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                let (u, v) = sampler.sample_2d((x, y), 0, 0);
+                let c = Color::new(0.5 + (u - 0.5) * 0.4, 0.25, 0.5 + (v - 0.5) * 0.4);
+                canvas.put_pixel(c, (x, y));
+            }
+        }
+
+        canvas
+    }
+
+    #[test]
+    fn test_first_frame_is_taken_as_is() {
+        let mut acc = TemporalAccumulator::new(4, 4, 0.1);
+        let frame = noisy_frame(0);
+
+        acc.accumulate(&frame);
+
+        assert_eq!(acc.frames(), 1);
+        assert_eq!(acc.canvas().get_pixel((2, 3)), frame.get_pixel((2, 3)));
+    }
+
+    #[test]
+    fn test_accumulating_identical_frames_keeps_them_unchanged() {
+        let mut acc = TemporalAccumulator::new(4, 4, 0.2);
+        let frame = noisy_frame(3);
+
+        for _ in 0..10 {
+            acc.accumulate(&frame);
+        }
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let a = acc.canvas().get_pixel((x, y));
+                let b = frame.get_pixel((x, y));
+                assert!((a.r - b.r).abs() < 1e-12 && (a.b - b.b).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_accumulating_noisy_frames_approaches_the_noise_free_reference() {
+        let mut acc = TemporalAccumulator::new(4, 4, 0.02);
+        let error = |canvas: &Canvas| {
+            let mut worst: f64 = 0.0;
+            for y in 0..4 {
+                for x in 0..4 {
+                    let c = canvas.get_pixel((x, y));
+                    worst = worst.max((c.r - 0.5).abs()).max((c.b - 0.5).abs());
+                }
+            }
+            worst
+        };
+
+        acc.accumulate(&noisy_frame(0));
+        let first = error(acc.canvas());
+        for frame in 1..200 {
+            acc.accumulate(&noisy_frame(frame));
+        }
+
+        assert!(error(acc.canvas()) < 0.05);
+        assert!(error(acc.canvas()) < first);
+    }
+
+    #[test]
+    fn test_reset_discards_history() {
+        let mut acc = TemporalAccumulator::new(4, 4, 0.5);
+        acc.accumulate(&noisy_frame(0));
+
+        acc.reset();
+
+        assert_eq!(acc.frames(), 0);
+        assert_eq!(acc.canvas().get_pixel((0, 0)), Color::new(0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,162 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::tuple::Elem;
+
+/// Tolerance used by [`ApproxEq::approx_eq_default`] and
+/// [`assert_approx_eq!`](crate::assert_approx_eq) when none is given;
+/// looser under the `f32` feature.
+#[cfg(not(feature = "f32"))]
+pub const DEFAULT_EPSILON: Elem = 1e-6;
+#[cfg(feature = "f32")]
+pub const DEFAULT_EPSILON: Elem = 1e-4;
+
+/// Equality up to a per-element absolute difference.
+pub trait ApproxEq {
+    /// The elements compared, in a fixed order. Matrices list theirs row by
+    /// row.
+    fn approx_elements(&self) -> Cow<'_, [Elem]>;
+
+    /// Whether every element of `self` is within `epsilon` of the matching
+    /// element of `other`.
+    fn approx_eq(&self, other: &Self, epsilon: Elem) -> bool {
+        elements_approx_eq(&self.approx_elements(), &other.approx_elements(), epsilon)
+    }
+
+    fn approx_eq_default(&self, other: &Self) -> bool {
+        self.approx_eq(other, DEFAULT_EPSILON)
+    }
+}
+
+/// Adapts a test tolerance written for `f64` to the element type: returned
+/// as is for `f64`, raised to at least [`DEFAULT_EPSILON`] under `f32`.
+#[cfg(test)]
+pub(crate) const fn tolerance(epsilon: Elem) -> Elem {
+    #[cfg(feature = "f32")]
+    let epsilon = epsilon.max(DEFAULT_EPSILON);
+
+    epsilon
+}
+
+fn elements_approx_eq(a: &[Elem], b: &[Elem], epsilon: Elem) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() <= epsilon)
+}
+
+/// One line per element pair, with the ones further apart than `epsilon`
+/// marked. Used by [`assert_approx_eq!`](crate::assert_approx_eq) to point
+/// at what actually differs.
+#[doc(hidden)]
+pub fn element_diff<T: ApproxEq + ?Sized>(left: &T, right: &T, epsilon: Elem) -> String {
+    let (left, right) = (left.approx_elements(), right.approx_elements());
+    let mut diff = String::new();
+
+    for (i, (l, r)) in left.iter().zip(right.iter()).enumerate() {
+        let delta = (l - r).abs();
+        let _ = write!(diff, "\n  [{}] {:?} vs {:?}", i, l, r);
+        if delta.is_nan() || delta > epsilon {
+            let _ = write!(diff, "  <- off by {:?}", delta);
+        }
+    }
+    if left.len() != right.len() {
+        let _ = write!(diff, "\n  {} vs {} elements", left.len(), right.len());
+    }
+
+    diff
+}
+
+/// Asserts that two [`ApproxEq`] values are equal within an epsilon,
+/// [`DEFAULT_EPSILON`] unless one is passed as the third argument.
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_approx_eq!($left, $right, $crate::approx::DEFAULT_EPSILON)
+    };
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {
+        match (&$left, &$right, $epsilon) {
+            (left, right, epsilon) => {
+                if !$crate::approx::ApproxEq::approx_eq(left, right, epsilon) {
+                    panic!(
+                        "assertion `left ≈ right` failed (epsilon = {})\n  left: {:?}\n right: {:?}\n  diff:{}",
+                        epsilon,
+                        left,
+                        right,
+                        $crate::approx::element_diff(left, right, epsilon)
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elements_within_epsilon_are_equal() {
+        assert!(elements_approx_eq(
+            &[1.0, 2.0],
+            &[1.0 + 1e-7, 2.0 - 1e-7],
+            1e-6
+        ));
+        assert!(!elements_approx_eq(&[1.0, 2.0], &[1.0, 2.0 + 1e-5], 1e-6));
+    }
+
+    #[test]
+    fn test_elements_of_different_lengths_are_not_equal() {
+        assert!(!elements_approx_eq(&[1.0], &[1.0, 2.0], 1e-6));
+    }
+
+    #[test]
+    #[cfg_attr(
+        not(feature = "f32"),
+        should_panic(expected = "assertion `left ≈ right` failed (epsilon = 0.000001)")
+    )]
+    #[cfg_attr(
+        feature = "f32",
+        should_panic(expected = "assertion `left ≈ right` failed (epsilon = 0.0001)")
+    )]
+    fn test_assert_approx_eq_panics_with_both_values() {
+        use crate::tuple::Tuple4;
+
+        assert_approx_eq!(Tuple4::point(1.0, 0.0, 0.0), Tuple4::point(1.1, 0.0, 0.0));
+    }
+
+    fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+        let payload = std::panic::catch_unwind(f).unwrap_err();
+        payload.downcast::<String>().map(|s| *s).unwrap()
+    }
+
+    #[test]
+    fn test_assert_approx_eq_marks_the_elements_over_epsilon() {
+        use crate::tuple::Tuple4;
+
+        let message = panic_message(|| {
+            assert_approx_eq!(
+                Tuple4::point(1.0, 2.0, 3.0),
+                Tuple4::point(1.0, 2.5, 3.0),
+                0.25
+            )
+        });
+
+        assert!(message.contains("  diff:\n  [0] 1.0 vs 1.0\n  [1] 2.0 vs 2.5  <- off by 0.5\n"));
+        assert!(message.contains("\n  [2] 3.0 vs 3.0\n  [3] 1.0 vs 1.0"));
+        assert!(!message.contains("[3] 1.0 vs 1.0  <-"));
+    }
+
+    #[test]
+    fn test_assert_approx_eq_diffs_matrices_row_by_row() {
+        use crate::matrix::Matrix2x2;
+
+        let message = panic_message(|| {
+            assert_approx_eq!(
+                Matrix2x2::from([[1.0, 2.0], [3.0, 4.0]]),
+                Matrix2x2::from([[1.0, 2.0], [3.0, 5.0]]),
+                0.5
+            )
+        });
+
+        assert!(message.starts_with("assertion `left ≈ right` failed (epsilon = 0.5)"));
+        assert!(message.contains("  [2] 3.0 vs 3.0\n  [3] 4.0 vs 5.0  <- off by 1.0"));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    matrix::Matrix4x4,
+    tuple::{Elem, Tuple4},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Tuple4,
+    pub radius: Elem,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Tuple4, radius: Elem) -> Self {
+        BoundingSphere { center, radius }
+    }
+
+    pub fn from_box(min: Tuple4, max: Tuple4) -> Self {
+        let center = Tuple4::point(
+            (min.x + max.x) / 2.0,
+            (min.y + max.y) / 2.0,
+            (min.z + max.z) / 2.0,
+        );
+        let radius = (max - center).magnitude();
+
+        BoundingSphere { center, radius }
+    }
+
+    pub fn contains(&self, point: Tuple4) -> bool {
+        (point - self.center).magnitude() <= self.radius
+    }
+
+    pub fn merge(&self, other: &BoundingSphere) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.magnitude();
+
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+
+        let radius = (distance + self.radius + other.radius) / 2.0;
+        let center = self.center + offset * ((radius - self.radius) / distance);
+
+        BoundingSphere { center, radius }
+    }
+
+    /// Bounds the image of the sphere under `m`.
+    ///
+    /// The radius grows by the largest stretch `m` can apply to a vector.
+    /// That is exact when the linear part has orthogonal rows or columns
+    /// (any mix of rotations and per-axis scaling) and falls back to the
+    /// Frobenius norm, which over-estimates, when shear is involved.
+    pub fn transform(&self, m: Matrix4x4) -> Self {
+        let center = m * self.center;
+        let radius = self.radius * max_stretch(&m);
+
+        BoundingSphere { center, radius }
+    }
+}
+
+fn max_stretch(m: &Matrix4x4) -> Elem {
+    let col = |i: usize| Tuple4::vector(m.get(0, i), m.get(1, i), m.get(2, i));
+    let row = |i: usize| Tuple4::vector(m.get(i, 0), m.get(i, 1), m.get(i, 2));
+    let cols = [col(0), col(1), col(2)];
+    let rows = [row(0), row(1), row(2)];
+
+    let orthogonal = |v: &[Tuple4; 3]| {
+        let scale = v.iter().map(|a| a.dot(a)).fold(0.0, Elem::max) + Elem::MIN_POSITIVE;
+        [(0, 1), (0, 2), (1, 2)]
+            .iter()
+            .all(|&(i, j)| v[i].dot(&v[j]).abs() <= 1e-9 * scale)
+    };
+    let max_length = |v: &[Tuple4; 3]| v.iter().map(|a| a.magnitude()).fold(0.0, Elem::max);
+
+    if orthogonal(&cols) {
+        max_length(&cols)
+    } else if orthogonal(&rows) {
+        max_length(&rows)
+    } else {
+        cols.iter().map(|a| a.dot(a)).sum::<Elem>().sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tuple::consts::PI;
+
+    use super::*;
+    use crate::test_rng::Lcg;
+
+    const EPSILON: Elem = 1e-9;
+
+    fn random_transform(rng: &mut Lcg) -> Matrix4x4 {
+        Matrix4x4::translation(
+            rng.range(-5.0, 5.0),
+            rng.range(-5.0, 5.0),
+            rng.range(-5.0, 5.0),
+        ) * Matrix4x4::rotation_x(rng.range(0.0, 2.0 * PI))
+            * Matrix4x4::rotation_y(rng.range(0.0, 2.0 * PI))
+            * Matrix4x4::shearing(
+                rng.range(-1.0, 1.0),
+                rng.range(-1.0, 1.0),
+                0.0,
+                rng.range(-1.0, 1.0),
+                0.0,
+                0.0,
+            )
+            * Matrix4x4::scaling(
+                rng.range(0.1, 3.0),
+                rng.range(0.1, 3.0),
+                rng.range(0.1, 3.0),
+            )
+    }
+
+    #[test]
+    fn test_bounding_sphere_from_box_contains_its_corners() {
+        let min = Tuple4::point(-1.0, -2.0, -3.0);
+        let max = Tuple4::point(3.0, 2.0, 1.0);
+
+        let s = BoundingSphere::from_box(min, max);
+
+        assert_eq!(s.center, Tuple4::point(1.0, 0.0, -1.0));
+        assert!((s.radius - Elem::sqrt(12.0)).abs() < EPSILON);
+        assert!(s.contains(min));
+        assert!(s.contains(max));
+    }
+
+    #[test]
+    fn test_merging_disjoint_spheres() {
+        let a = BoundingSphere::new(Tuple4::point(-2.0, 0.0, 0.0), 1.0);
+        let b = BoundingSphere::new(Tuple4::point(2.0, 0.0, 0.0), 1.0);
+
+        let m = a.merge(&b);
+
+        assert_eq!(m.center, Tuple4::point(0.0, 0.0, 0.0));
+        assert_eq!(m.radius, 3.0);
+    }
+
+    #[test]
+    fn test_merging_a_contained_sphere_keeps_the_outer_one() {
+        let outer = BoundingSphere::new(Tuple4::point(0.0, 0.0, 0.0), 5.0);
+        let inner = BoundingSphere::new(Tuple4::point(1.0, 1.0, 0.0), 1.0);
+
+        assert_eq!(outer.merge(&inner), outer);
+        assert_eq!(inner.merge(&outer), outer);
+    }
+
+    #[test]
+    fn test_transforming_by_non_uniform_scaling_takes_the_largest_axis() {
+        let s = BoundingSphere::new(Tuple4::point(0.0, 0.0, 0.0), 1.0);
+
+        let t =
+            s.transform(Matrix4x4::translation(1.0, 0.0, 0.0) * Matrix4x4::scaling(1.0, 4.0, 2.0));
+
+        assert_eq!(t.center, Tuple4::point(1.0, 0.0, 0.0));
+        assert!((t.radius - 4.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_transformed_bounding_sphere_contains_transformed_box_corners() {
+        let mut rng = Lcg::new(17);
+        let min = Tuple4::point(-1.0, -0.5, -2.0);
+        let max = Tuple4::point(1.0, 1.5, 0.0);
+        let bound = BoundingSphere::from_box(min, max);
+
+        for _ in 0..500 {
+            let m = random_transform(&mut rng);
+            let t = bound.transform(m);
+            for i in 0..8 {
+                let corner = Tuple4::point(
+                    if i & 1 == 0 { min.x } else { max.x },
+                    if i & 2 == 0 { min.y } else { max.y },
+                    if i & 4 == 0 { min.z } else { max.z },
+                );
+                let p = m * corner;
+                assert!((p - t.center).magnitude() <= t.radius + EPSILON);
+            }
+        }
+    }
+}
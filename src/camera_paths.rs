@@ -0,0 +1,161 @@
+use crate::tuple::consts::PI;
+
+use crate::{
+    approx::DEFAULT_EPSILON,
+    matrix::Matrix4x4,
+    tuple::{Elem, Tuple4},
+};
+
+/// View transforms orbiting `center` once, `height` above it.
+///
+/// Returns `frames + 1` transforms: the last one closes the loop and
+/// equals the first, so drop it when playing the sequence on repeat.
+pub fn turntable(center: Tuple4, radius: Elem, height: Elem, frames: usize) -> Vec<Matrix4x4> {
+    assert!(frames > 0, "Turntable needs at least one frame");
+
+    (0..=frames)
+        .map(|i| {
+            let angle = 2.0 * PI * (i % frames) as Elem / frames as Elem;
+            let from = center + Tuple4::vector(radius * angle.sin(), height, -radius * angle.cos());
+            look_at_point(from, center)
+        })
+        .collect()
+}
+
+/// View transforms looking at `look_at` from positions on a Catmull–Rom
+/// spline through `points`.
+///
+/// Control point `j` is reached at frame `j * (frames - 1) / (points.len() - 1)`.
+pub fn spline_through(points: &[Tuple4], look_at: Tuple4, frames: usize) -> Vec<Matrix4x4> {
+    assert!(points.len() >= 2, "Spline needs at least two points");
+    assert!(frames >= 2, "Spline needs at least two frames");
+
+    let segments = points.len() - 1;
+    (0..frames)
+        .map(|i| {
+            let s = i as Elem * segments as Elem / (frames - 1) as Elem;
+            let segment = (s.floor() as usize).min(segments - 1);
+            let from = catmull_rom(points, segment, s - segment as Elem);
+            look_at_point(from, look_at)
+        })
+        .collect()
+}
+
+// Removes the forward component from world up so the view's rotation part
+// stays orthonormal when the camera looks up or down. Looking straight up
+// or down leaves nothing of +y, so +z stands in as world up there.
+fn look_at_point(from: Tuple4, to: Tuple4) -> Matrix4x4 {
+    let forward = (to - from).normalize();
+    let mut world_up = Tuple4::vector(0.0, 1.0, 0.0);
+    if forward.cross(world_up).magnitude_squared() < DEFAULT_EPSILON {
+        world_up = Tuple4::vector(0.0, 0.0, 1.0);
+    }
+    let up = world_up - forward * forward.dot(&world_up);
+
+    Matrix4x4::view_transform(from, to, up)
+}
+
+fn catmull_rom(points: &[Tuple4], segment: usize, t: Elem) -> Tuple4 {
+    let p1 = points[segment];
+    let p2 = points[segment + 1];
+    let p0 = if segment == 0 {
+        p1
+    } else {
+        points[segment - 1]
+    };
+    let p3 = *points.get(segment + 2).unwrap_or(&p2);
+
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let a = p1 * 2.0;
+    let b = (p2 - p0) * t;
+    let c = (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2;
+    let d = (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3;
+
+    (a + b + c + d) * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::tolerance;
+
+    const EPSILON: Elem = tolerance(1e-9);
+
+    fn eye(view: &Matrix4x4) -> Tuple4 {
+        view.inverse().unwrap() * Tuple4::point(0.0, 0.0, 0.0)
+    }
+
+    fn looks_at(view: &Matrix4x4, target: Tuple4) -> bool {
+        let p = *view * target;
+        p.x.abs() < EPSILON && p.y.abs() < EPSILON && p.z < 0.0
+    }
+
+    #[test]
+    fn test_turntable_is_a_closed_loop() {
+        let views = turntable(Tuple4::point(1.0, 0.0, 2.0), 5.0, 1.0, 12);
+
+        assert_eq!(views.len(), 13);
+        assert_eq!(views[0], views[12]);
+        assert_ne!(views[0], views[6]);
+    }
+
+    #[test]
+    fn test_turntable_orbits_and_looks_at_the_center() {
+        let center = Tuple4::point(1.0, 0.5, 2.0);
+        let views = turntable(center, 5.0, 2.0, 8);
+
+        for view in &views {
+            assert!(view.is_rotation());
+            assert!(looks_at(view, center));
+
+            let offset = eye(view) - center;
+            assert!((offset.y - 2.0).abs() < EPSILON);
+            assert!(((offset.x * offset.x + offset.z * offset.z).sqrt() - 5.0).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_turntable_looks_straight_down_with_zero_radius() {
+        let center = Tuple4::point(1.0, 0.5, 2.0);
+        let views = turntable(center, 0.0, 3.0, 4);
+
+        for view in &views {
+            assert!(view.is_rotation());
+            assert!(looks_at(view, center));
+        }
+    }
+
+    #[test]
+    fn test_spline_point_directly_above_or_below_look_at() {
+        let points = [Tuple4::point(0.0, 5.0, 0.0), Tuple4::point(0.0, -5.0, 0.0)];
+        let look_at = Tuple4::point(0.0, 0.0, 0.0);
+
+        for view in &spline_through(&points, look_at, 2) {
+            assert!(view.is_rotation());
+            assert!(looks_at(view, look_at));
+        }
+    }
+
+    #[test]
+    fn test_spline_passes_through_control_points() {
+        let points = [
+            Tuple4::point(-4.0, 1.0, -6.0),
+            Tuple4::point(0.0, 2.0, -8.0),
+            Tuple4::point(4.0, 1.0, -6.0),
+        ];
+        let look_at = Tuple4::point(0.0, 0.0, 0.0);
+
+        let views = spline_through(&points, look_at, 9);
+
+        assert_eq!(views.len(), 9);
+        for (frame, point) in [(0, points[0]), (4, points[1]), (8, points[2])] {
+            let e = eye(&views[frame]);
+            assert!((e - point).magnitude() < EPSILON, "frame {}", frame);
+        }
+        for view in &views {
+            assert!(view.is_rotation());
+            assert!(looks_at(view, look_at));
+        }
+    }
+}
@@ -1,14 +1,152 @@
-use crate::{color::Color, ppm::PPM};
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{self, BufRead, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    color::Color,
+    ppm::{self, PPMEncoder, PpmParseError, PPM},
+};
+
+/// Error returned by [`Canvas::write_png`].
+#[cfg(feature = "png")]
+pub use png::EncodingError as PngError;
+
+/// How a [`Canvas`] stores its pixels.
+///
+/// `F64` keeps colors exactly as written. `F32` halves the memory per pixel
+/// and rounds each channel to the nearest `f32`, a relative error of at most
+/// `f32::EPSILON / 2`. `Rgbe8` packs a pixel into four bytes with a shared
+/// exponent: negative channels are clamped to zero and every channel is off
+/// by at most `max(r, g, b) / 255`, so dim channels next to a bright one lose
+/// the most.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum CanvasStorage {
+    #[default]
+    F64,
+    F32,
+    Rgbe8,
+}
+
+/// Tone mapping applied to linear colors when exporting a [`Canvas`].
+///
+/// Colors are first multiplied by `exposure`, then encoded with the exact
+/// sRGB curve if `srgb` is set, or raised to `1 / gamma` otherwise. A gamma
+/// of 1.0 with unit exposure leaves colors untouched, matching the plain
+/// exporters byte for byte. The exporters panic on a gamma that isn't
+/// positive unless `srgb` is set, since the sRGB curve ignores it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ExportOptions {
+    pub gamma: f64,
+    pub exposure: f64,
+    pub srgb: bool,
+}
+
+impl ExportOptions {
+    fn validate(&self) {
+        assert!(
+            self.srgb || self.gamma > 0.0,
+            "Export gamma must be positive, got {}",
+            self.gamma
+        );
+    }
+
+    fn encode(&self, channel: f64) -> f64 {
+        let c = (channel * self.exposure).max(0.0);
+        if self.srgb {
+            if c <= 0.0031308 {
+                12.92 * c
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        } else {
+            c.powf(1.0 / self.gamma)
+        }
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            gamma: 2.2,
+            exposure: 1.0,
+            srgb: false,
+        }
+    }
+}
+
+type F32Pixel = [f32; 3];
+type Rgbe8Pixel = [u8; 4];
+
+enum Pixels {
+    F64(Vec<Color>),
+    F32(Vec<F32Pixel>),
+    Rgbe8(Vec<Rgbe8Pixel>),
+}
+
+fn to_f32(c: Color) -> F32Pixel {
+    [c.r as f32, c.g as f32, c.b as f32]
+}
+
+fn from_f32(p: F32Pixel) -> Color {
+    Color::new(p[0] as f64, p[1] as f64, p[2] as f64)
+}
+
+fn to_rgbe8(c: Color) -> Rgbe8Pixel {
+    let clamp = |n: f64| n.clamp(0.0, f64::MAX);
+    let (r, g, b) = (clamp(c.r), clamp(c.g), clamp(c.b));
+    let max = r.max(g).max(b);
+    if max < f64::MIN_POSITIVE {
+        return [0, 0, 0, 0];
+    }
+
+    let mut exponent = max.log2().floor() as i32 + 1;
+    if max >= 2f64.powi(exponent) {
+        exponent += 1;
+    }
+    if (max * 2f64.powi(8 - exponent)).round() >= 256.0 {
+        exponent += 1;
+    }
+    if exponent < -127 {
+        return [0, 0, 0, 0];
+    }
+    let exponent = exponent.min(127);
+
+    let scale = 2f64.powi(8 - exponent);
+    let channel = |n: f64| (n * scale).round() as u8;
+    [channel(r), channel(g), channel(b), (exponent + 128) as u8]
+}
+
+fn from_rgbe8(p: Rgbe8Pixel) -> Color {
+    if p[3] == 0 {
+        return Color::BLACK;
+    }
+
+    let scale = 2f64.powi(p[3] as i32 - 128 - 8);
+    let channel = |n: u8| n as f64 * scale;
+    Color::new(channel(p[0]), channel(p[1]), channel(p[2]))
+}
 
 pub struct Canvas {
     width: usize,
     height: usize,
-    pixels: Vec<Color>,
+    pixels: Pixels,
 }
 
 impl Canvas {
     pub fn new(width: usize, height: usize) -> Canvas {
-        let pixels = vec![Color::new(0.0, 0.0, 0.0); width * height];
+        Canvas::with_storage(width, height, CanvasStorage::F64)
+    }
+
+    pub fn with_storage(width: usize, height: usize, storage: CanvasStorage) -> Canvas {
+        let black = Color::BLACK;
+        let pixels = match storage {
+            CanvasStorage::F64 => Pixels::F64(vec![black; width * height]),
+            CanvasStorage::F32 => Pixels::F32(vec![to_f32(black); width * height]),
+            CanvasStorage::Rgbe8 => Pixels::Rgbe8(vec![to_rgbe8(black); width * height]),
+        };
 
         Canvas {
             width,
@@ -19,26 +157,340 @@ impl Canvas {
 
     fn to_index(&self, pos: (usize, usize)) -> usize {
         let (x, y) = pos;
+        assert!(
+            self.contains(pos),
+            "Pixel ({}, {}) out of bounds for {}x{} canvas",
+            x,
+            y,
+            self.width,
+            self.height
+        );
 
         y * self.width + x
     }
 
-    pub fn get_width(&self) -> usize {
+    fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn width(&self) -> usize {
         self.width
     }
 
-    pub fn get_height(&self) -> usize {
+    pub fn height(&self) -> usize {
         self.height
     }
 
+    pub fn storage(&self) -> CanvasStorage {
+        match self.pixels {
+            Pixels::F64(_) => CanvasStorage::F64,
+            Pixels::F32(_) => CanvasStorage::F32,
+            Pixels::Rgbe8(_) => CanvasStorage::Rgbe8,
+        }
+    }
+
     pub fn put_pixel(&mut self, pixel: Color, at: (usize, usize)) {
         let i = self.to_index(at);
-        self.pixels[i] = pixel;
+        match &mut self.pixels {
+            Pixels::F64(pixels) => pixels[i] = pixel,
+            Pixels::F32(pixels) => pixels[i] = to_f32(pixel),
+            Pixels::Rgbe8(pixels) => pixels[i] = to_rgbe8(pixel),
+        }
+    }
+
+    /// Writes a pixel, silently dropping writes that fall off the canvas.
+    pub fn write_pixel(&mut self, x: usize, y: usize, color: Color) {
+        self.try_write_pixel(x, y, color);
+    }
+
+    /// Writes a pixel, returning `false` and leaving the canvas unchanged
+    /// if `(x, y)` is off the canvas.
+    pub fn try_write_pixel(&mut self, x: usize, y: usize, color: Color) -> bool {
+        let inside = self.contains((x, y));
+        if inside {
+            self.put_pixel(color, (x, y));
+        }
+        inside
     }
 
-    pub fn get_pixel(&self, at: (usize, usize)) -> &Color {
+    /// Draws a line from `(x0, y0)` to `(x1, y1)`, both ends included, with
+    /// Bresenham's algorithm. Parts off the canvas are clipped.
+    ///
+    /// Pixel `i` along the major axis has its minor coordinate worked out
+    /// directly rather than accumulated, so the walk can start and stop at
+    /// the canvas edges instead of stepping through every off-canvas pixel.
+    /// The pixels drawn are the same as for the unclipped line.
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+        let (x0, y0, x1, y1) = (x0 as i64, y0 as i64, x1 as i64, y1 as i64);
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let steps = dx.abs().max(dy.abs());
+        if steps == 0 {
+            self.plot(x0, y0, color);
+            return;
+        }
+
+        // Offset along an axis of extent `d` after `i` of `steps` steps,
+        // rounded half up. The product needs more than 64 bits for lines
+        // spanning the whole i32 range.
+        let offset = |i: i64, d: i64| {
+            let (i, n, m) = (i as i128, steps as i128, d.abs() as i128);
+            d.signum() * ((2 * i * m + n) / (2 * n)) as i64
+        };
+
+        let x_major = dx.abs() >= dy.abs();
+        let (start, step, len) = if x_major {
+            (x0, dx.signum(), self.width as i64)
+        } else {
+            (y0, dy.signum(), self.height as i64)
+        };
+        // The steps that keep the major coordinate on the canvas.
+        let (first, last) = if step > 0 {
+            (-start, len - 1 - start)
+        } else {
+            (start - (len - 1), start)
+        };
+
+        for i in first.max(0)..=last.min(steps) {
+            let (x, y) = if x_major {
+                (start + step * i, y0 + offset(i, dy))
+            } else {
+                (x0 + offset(i, dx), start + step * i)
+            };
+            self.plot(x, y, color);
+        }
+    }
+
+    /// Draws the outline of a circle with the midpoint algorithm. Parts off
+    /// the canvas are clipped.
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, color: Color) {
+        let (cx, cy) = (cx as i64, cy as i64);
+        let (mut x, mut y) = (radius as i64, 0);
+        let mut d = 1 - x;
+
+        while x >= y {
+            for (px, py) in [(x, y), (y, x)] {
+                self.plot(cx + px, cy + py, color);
+                self.plot(cx - px, cy + py, color);
+                self.plot(cx + px, cy - py, color);
+                self.plot(cx - px, cy - py, color);
+            }
+            y += 1;
+            if d < 0 {
+                d += 2 * y + 1;
+            } else {
+                x -= 1;
+                d += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// Fills the rectangle with corners `(x0, y0)` and `(x1, y1)`, both
+    /// included, clipped to the canvas.
+    pub fn fill_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+        let clip = |a: i32, b: i32, len: usize| {
+            let max = len as i64 - 1;
+            let (lo, hi) = (a.min(b) as i64, a.max(b) as i64);
+            (lo.max(0), hi.min(max))
+        };
+        let (left, right) = clip(x0, x1, self.width);
+        let (top, bottom) = clip(y0, y1, self.height);
+
+        for y in top..=bottom {
+            for x in left..=right {
+                self.put_pixel(color, (x as usize, y as usize));
+            }
+        }
+    }
+
+    /// Copies `src` with its top-left corner at `(dst_x, dst_y)`. Parts of
+    /// `src` that land off this canvas are dropped.
+    pub fn blit(&mut self, src: &Canvas, dst_x: i32, dst_y: i32) {
+        for (x, y, color) in src.pixels() {
+            self.plot(dst_x as i64 + x as i64, dst_y as i64 + y as i64, color);
+        }
+    }
+
+    /// Copies the `width` by `height` region whose top-left corner is at
+    /// `(x, y)` into a new canvas with the same storage.
+    ///
+    /// Panics if the region extends past the canvas.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Canvas {
+        assert!(
+            x + width <= self.width && y + height <= self.height,
+            "Region {}x{} at ({}, {}) out of bounds for {}x{} canvas",
+            width,
+            height,
+            x,
+            y,
+            self.width,
+            self.height
+        );
+
+        let mut canvas = Canvas::with_storage(width, height, self.storage());
+        for row in 0..height {
+            for col in 0..width {
+                canvas.put_pixel(self.get_pixel((x + col, y + row)), (col, row));
+            }
+        }
+        canvas
+    }
+
+    /// Mirrors the canvas top to bottom.
+    pub fn flip_vertical(&mut self) {
+        for y in 0..self.height / 2 {
+            for x in 0..self.width {
+                self.swap((x, y), (x, self.height - 1 - y));
+            }
+        }
+    }
+
+    /// Mirrors the canvas left to right.
+    pub fn flip_horizontal(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width / 2 {
+                self.swap((x, y), (self.width - 1 - x, y));
+            }
+        }
+    }
+
+    fn swap(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let (i, j) = (self.to_index(a), self.to_index(b));
+        match &mut self.pixels {
+            Pixels::F64(pixels) => pixels.swap(i, j),
+            Pixels::F32(pixels) => pixels.swap(i, j),
+            Pixels::Rgbe8(pixels) => pixels.swap(i, j),
+        }
+    }
+
+    fn plot(&mut self, x: i64, y: i64, color: Color) {
+        if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) {
+            self.try_write_pixel(x, y, color);
+        }
+    }
+
+    /// Panics if `(x, y)` is off the canvas.
+    pub fn pixel_at(&self, x: usize, y: usize) -> Color {
+        self.get_pixel((x, y))
+    }
+
+    /// Every pixel with its position, row by row. Colors are yielded by
+    /// value since compact storages don't hold a `Color` to borrow.
+    pub fn pixels(&self) -> impl Iterator<Item = (usize, usize, Color)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y, self.get_pixel((x, y)))))
+    }
+
+    /// Reads a P3 or P6 PPM, scaling channels by its maximum color value.
+    pub fn from_ppm<R: BufRead>(reader: R) -> Result<Canvas, PpmParseError> {
+        let image = ppm::decode(reader)?;
+        let mut canvas = Canvas::new(image.width, image.height);
+        for (i, [r, g, b]) in image.pixels.into_iter().enumerate() {
+            canvas.put_pixel(Color::new(r, g, b), (i % image.width, i / image.width));
+        }
+
+        Ok(canvas)
+    }
+
+    /// Writes the canvas as a binary P6 PPM.
+    pub fn write_ppm_binary<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        PPMEncoder::new(&mut writer).write_binary(self)
+    }
+
+    /// Renders the canvas as a text P3 PPM after tone mapping it.
+    pub fn to_ppm_with(&self, options: &ExportOptions) -> String {
+        options.validate();
+        let mut buffer = Vec::new();
+        PPMEncoder::new(&mut buffer)
+            .write(&ToneMapped {
+                canvas: self,
+                options,
+            })
+            .expect("writing to a Vec can't fail");
+        String::from_utf8(buffer).expect("PPM output is ASCII")
+    }
+
+    /// Like [`Canvas::write_png`], tone mapping the canvas first.
+    #[cfg(feature = "png")]
+    pub fn write_png_with<W: Write>(
+        &self,
+        writer: W,
+        options: &ExportOptions,
+    ) -> Result<(), PngError> {
+        options.validate();
+        encode_png(
+            &ToneMapped {
+                canvas: self,
+                options,
+            },
+            writer,
+        )
+    }
+
+    /// Saves the canvas to `path` as a binary P6 PPM.
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_ppm_binary(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Writes the canvas as an 8-bit RGB PNG, with channels quantized the
+    /// same way as the PPM exporters.
+    #[cfg(feature = "png")]
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), PngError> {
+        encode_png(self, writer)
+    }
+
+    pub fn get_pixel(&self, at: (usize, usize)) -> Color {
         let i = self.to_index(at);
-        &self.pixels[i]
+        match &self.pixels {
+            Pixels::F64(pixels) => pixels[i],
+            Pixels::F32(pixels) => from_f32(pixels[i]),
+            Pixels::Rgbe8(pixels) => from_rgbe8(pixels[i]),
+        }
+    }
+}
+
+/// Streams an image into an 8-bit RGB PNG a row at a time.
+#[cfg(feature = "png")]
+fn encode_png<P: PPM<Color>, W: Write>(image: &P, writer: W) -> Result<(), PngError> {
+    let mut encoder = png::Encoder::new(writer, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    let mut stream = writer.stream_writer()?;
+    for y in 0..image.height() {
+        stream.write_all(&ppm::rgb_bytes(&image.row(y)))?;
+    }
+    stream.finish()?;
+    writer.finish()
+}
+
+/// A canvas seen through [`ExportOptions`], tone mapped row by row as it
+/// is encoded rather than copied up front.
+struct ToneMapped<'a> {
+    canvas: &'a Canvas,
+    options: &'a ExportOptions,
+}
+
+impl PPM<Color> for ToneMapped<'_> {
+    fn width(&self) -> usize {
+        self.canvas.width
+    }
+
+    fn height(&self) -> usize {
+        self.canvas.height
+    }
+
+    fn row(&self, y: usize) -> Cow<'_, [Color]> {
+        let encode = |c: &Color| {
+            Color::new(
+                self.options.encode(c.r),
+                self.options.encode(c.g),
+                self.options.encode(c.b),
+            )
+        };
+        self.canvas.row(y).iter().map(encode).collect()
     }
 }
 
@@ -47,7 +499,19 @@ impl IntoIterator for Canvas {
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.pixels.into_iter()
+        match self.pixels {
+            Pixels::F64(pixels) => pixels.into_iter(),
+            Pixels::F32(pixels) => pixels
+                .into_iter()
+                .map(from_f32)
+                .collect::<Vec<_>>()
+                .into_iter(),
+            Pixels::Rgbe8(pixels) => pixels
+                .into_iter()
+                .map(from_rgbe8)
+                .collect::<Vec<_>>()
+                .into_iter(),
+        }
     }
 }
 
@@ -60,21 +524,95 @@ impl PPM<Color> for Canvas {
         self.height
     }
 
-    fn colors(&self) -> &[Color] {
-        &self.pixels
+    fn row(&self, y: usize) -> Cow<'_, [Color]> {
+        let row = y * self.width..(y + 1) * self.width;
+        match &self.pixels {
+            Pixels::F64(pixels) => Cow::Borrowed(&pixels[row]),
+            Pixels::F32(pixels) => pixels[row].iter().copied().map(from_f32).collect(),
+            Pixels::Rgbe8(pixels) => pixels[row].iter().copied().map(from_rgbe8).collect(),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        lights::PointLight,
+        materials::Material,
+        ray::Ray,
+        shape::Shape,
+        sphere::Sphere,
+        tuple::{Elem, Tuple4},
+    };
+
+    fn sample_colors() -> Vec<Color> {
+        let mut colors = vec![
+            Color::new(0.0, 0.0, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+            Color::new(1.0, 0.2, 1.0),
+            Color::new(0.001, 0.5, 0.999),
+            Color::new(12.5, 0.75, 3.0),
+            Color::new(1e-6, 2e-6, 3e-6),
+        ];
+        colors.extend((1..=50).map(|i| {
+            let t = i as f64 / 50.0;
+            Color::new(t, t * t, 1.0 - t)
+        }));
+        colors
+    }
+
+    fn round_trip(storage: CanvasStorage, color: Color) -> Color {
+        let mut canvas = Canvas::with_storage(1, 1, storage);
+        canvas.put_pixel(color, (0, 0));
+        canvas.get_pixel((0, 0))
+    }
+
+    // The sphere scene from `main`, at a lower resolution.
+    fn render_default_scene(storage: CanvasStorage) -> Canvas {
+        const PIXELS: usize = 64;
+        let pixel_size = 7.0 / PIXELS as Elem;
+        let half = 7.0 / 2.0;
+
+        let mut canvas = Canvas::with_storage(PIXELS, PIXELS, storage);
+        let ray_origin = Tuple4::point(0.0, 0.0, -5.0);
+        let mut sphere = Sphere::new();
+        sphere.set_material(Material {
+            color: Color::new(1.0, 0.2, 1.0),
+            ..Default::default()
+        });
+        let light = PointLight::new(
+            Tuple4::point(-10.0, -10.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        );
+
+        for y in 0..PIXELS {
+            let world_y = -half + pixel_size * y as Elem;
+            for x in 0..PIXELS {
+                let world_x = -half + pixel_size * x as Elem;
+                let pos = Tuple4::point(world_x, world_y, 10.0);
+                let ray = Ray::new(ray_origin, (pos - ray_origin).normalize());
+                let xs = sphere.intersect(&ray);
+
+                if let Some(hit) = xs.hit() {
+                    let point = ray.position(hit.t);
+                    let normal = hit.object.normal_at(point);
+                    let eye = -1.0 * ray.direction;
+                    let color = hit.object.material().lighting(light, point, eye, normal);
+                    canvas.put_pixel(color, (x, y));
+                }
+            }
+        }
+
+        canvas
+    }
 
     #[test]
     fn test_creating_new_canvas() {
         let canvas = Canvas::new(10, 20);
 
-        let width = canvas.get_width();
-        let height = canvas.get_height();
+        let width = canvas.width();
+        let height = canvas.height();
         let data: Vec<_> = canvas.into_iter().collect();
 
         assert_eq!(width, 10);
@@ -89,6 +627,728 @@ mod tests {
 
         canvas.put_pixel(pixel, (2, 3));
 
-        assert_eq!(*canvas.get_pixel((2, 3)), pixel);
+        assert_eq!(canvas.get_pixel((2, 3)), pixel);
+    }
+
+    #[test]
+    fn test_writing_and_reading_pixels() {
+        let mut canvas = Canvas::new(10, 20);
+        let red = Color::RED;
+
+        canvas.write_pixel(2, 3, red);
+
+        assert_eq!(canvas.pixel_at(2, 3), red);
+        assert_eq!(canvas.pixel_at(3, 2), Color::BLACK);
+    }
+
+    #[test]
+    fn test_writing_off_the_canvas_is_ignored() {
+        let mut canvas = Canvas::new(4, 3);
+
+        canvas.write_pixel(4, 0, Color::WHITE);
+        canvas.write_pixel(0, 3, Color::WHITE);
+
+        assert!(!canvas.try_write_pixel(10, 10, Color::WHITE));
+        assert!(canvas.try_write_pixel(3, 2, Color::WHITE));
+        assert!(canvas
+            .pixels()
+            .all(|(x, y, c)| (c == Color::WHITE) == ((x, y) == (3, 2))));
+    }
+
+    #[test]
+    #[should_panic(expected = "Pixel (4, 0) out of bounds for 4x3 canvas")]
+    fn test_reading_off_the_canvas_panics() {
+        Canvas::new(4, 3).pixel_at(4, 0);
+    }
+
+    #[test]
+    fn test_iterating_pixels_row_by_row() {
+        let mut canvas = Canvas::with_storage(3, 2, CanvasStorage::F32);
+        canvas.write_pixel(1, 1, Color::GRAY);
+
+        let pixels: Vec<_> = canvas.pixels().collect();
+
+        assert_eq!(pixels.len(), 6);
+        assert_eq!(pixels[1], (1, 0, Color::BLACK));
+        assert_eq!(pixels[3], (0, 1, Color::BLACK));
+        assert_eq!(pixels[4], (1, 1, Color::GRAY));
+    }
+
+    fn lit_pixels(canvas: &Canvas) -> Vec<(usize, usize)> {
+        canvas
+            .pixels()
+            .filter(|(_, _, c)| *c != Color::BLACK)
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_drawing_a_diagonal_line() {
+        let mut canvas = Canvas::new(5, 5);
+
+        canvas.draw_line(4, 4, 1, 1, Color::WHITE);
+
+        assert_eq!(lit_pixels(&canvas), vec![(1, 1), (2, 2), (3, 3), (4, 4)]);
+    }
+
+    #[test]
+    fn test_drawing_axis_aligned_lines() {
+        let mut canvas = Canvas::new(5, 5);
+
+        canvas.draw_line(1, 3, 3, 3, Color::WHITE);
+        canvas.draw_line(0, 2, 0, 0, Color::WHITE);
+
+        assert_eq!(
+            lit_pixels(&canvas),
+            vec![(0, 0), (0, 1), (0, 2), (1, 3), (2, 3), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn test_drawing_a_steep_line() {
+        let mut canvas = Canvas::new(5, 5);
+
+        canvas.draw_line(0, 0, 2, 4, Color::WHITE);
+
+        let lit = lit_pixels(&canvas);
+        assert_eq!(lit.len(), 5);
+        assert!(lit.contains(&(0, 0)) && lit.contains(&(2, 4)));
+        assert!(lit.iter().enumerate().all(|(i, &(_, y))| y == i));
+    }
+
+    #[test]
+    fn test_lines_are_clipped_to_the_canvas() {
+        let mut canvas = Canvas::new(5, 5);
+
+        canvas.draw_line(-10, -3, -1, -20, Color::WHITE);
+        canvas.draw_line(100, 0, 200, 4, Color::WHITE);
+        assert!(lit_pixels(&canvas).is_empty());
+
+        canvas.draw_line(-2, 2, 6, 2, Color::WHITE);
+        assert_eq!(
+            lit_pixels(&canvas),
+            (0..5).map(|x| (x, 2)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_lines_with_extreme_endpoints_do_not_overflow() {
+        let mut canvas = Canvas::new(4, 4);
+
+        canvas.draw_line(i32::MIN, 1, i32::MAX, 1, Color::WHITE);
+        canvas.draw_line(2, i32::MAX, 2, i32::MIN, Color::WHITE);
+        canvas.draw_line(i32::MIN, i32::MIN, i32::MAX, i32::MAX, Color::WHITE);
+
+        let lit = lit_pixels(&canvas);
+        assert!((0..4).all(|x| lit.contains(&(x, 1))));
+        assert!((0..4).all(|y| lit.contains(&(2, y))));
+    }
+
+    #[test]
+    fn test_lines_far_off_the_canvas_only_walk_the_visible_part() {
+        let mut canvas = Canvas::new(4, 4);
+
+        canvas.draw_line(-200_000_000, 0, 3, 3, Color::WHITE);
+        canvas.draw_line(-200_000_000, 2, 200_000_000, 2, Color::WHITE);
+
+        let expected: Vec<_> = (2..4).flat_map(|y| (0..4).map(move |x| (x, y))).collect();
+        assert_eq!(lit_pixels(&canvas), expected);
+    }
+
+    #[test]
+    fn test_clipping_draws_the_same_pixels_as_the_full_line() {
+        let lines = [
+            (-7, -3, 12, 9),
+            (9, -6, -4, 8),
+            (2, -20, 3, 25),
+            (-1, 0, 5, 4),
+        ];
+
+        for (x0, y0, x1, y1) in lines {
+            let mut small = Canvas::new(5, 5);
+            let mut large = Canvas::new(45, 55);
+            small.draw_line(x0, y0, x1, y1, Color::WHITE);
+            large.draw_line(x0 + 20, y0 + 25, x1 + 20, y1 + 25, Color::WHITE);
+
+            let expected = large.crop(20, 25, 5, 5);
+            assert_eq!(
+                lit_pixels(&small),
+                lit_pixels(&expected),
+                "line {:?}",
+                (x0, y0, x1, y1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_drawing_a_circle() {
+        let mut canvas = Canvas::new(9, 9);
+
+        canvas.draw_circle(4, 4, 3, Color::WHITE);
+
+        let lit = lit_pixels(&canvas);
+        assert_eq!(lit.len(), 16);
+        for (x, y) in [
+            (7, 4),
+            (1, 4),
+            (4, 7),
+            (4, 1),
+            (6, 6),
+            (2, 2),
+            (7, 5),
+            (3, 1),
+        ] {
+            assert!(lit.contains(&(x, y)), "({}, {}) not drawn", x, y);
+        }
+        assert!(!lit.contains(&(4, 4)));
+    }
+
+    #[test]
+    fn test_filling_a_clipped_rectangle() {
+        let mut canvas = Canvas::new(4, 4);
+
+        canvas.fill_rect(2, 5, -1, 2, Color::RED);
+
+        assert_eq!(
+            lit_pixels(&canvas),
+            vec![(0, 2), (1, 2), (2, 2), (0, 3), (1, 3), (2, 3)]
+        );
+    }
+
+    fn numbered_canvas(width: usize, height: usize) -> Canvas {
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                canvas.write_pixel(x, y, Color::new(x as f64, y as f64, 1.0));
+            }
+        }
+        canvas
+    }
+
+    #[test]
+    fn test_blitting_with_overhang() {
+        let src = numbered_canvas(3, 3);
+
+        for (dst_x, dst_y) in [(-2, -2), (3, -1), (-1, 3), (3, 3)] {
+            let mut canvas = Canvas::new(4, 4);
+
+            canvas.blit(&src, dst_x, dst_y);
+
+            for (x, y, c) in canvas.pixels() {
+                let (sx, sy) = (x as i32 - dst_x, y as i32 - dst_y);
+                let expected = if (0..3).contains(&sx) && (0..3).contains(&sy) {
+                    src.pixel_at(sx as usize, sy as usize)
+                } else {
+                    Color::BLACK
+                };
+                assert_eq!(
+                    c, expected,
+                    "({}, {}) blitting at ({}, {})",
+                    x, y, dst_x, dst_y
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_blitting_entirely_off_the_canvas() {
+        let mut canvas = Canvas::new(2, 2);
+
+        canvas.blit(&numbered_canvas(3, 3), -3, 5);
+
+        assert!(lit_pixels(&canvas).is_empty());
+    }
+
+    #[test]
+    fn test_crop_round_trips_with_blit() {
+        let canvas = numbered_canvas(5, 4);
+
+        let tile = canvas.crop(1, 2, 3, 2);
+        let mut restored = canvas.crop(0, 0, 5, 4);
+        restored.fill_rect(1, 2, 3, 3, Color::BLACK);
+        restored.blit(&tile, 1, 2);
+
+        assert_eq!((tile.width(), tile.height()), (3, 2));
+        assert_eq!(tile.pixel_at(0, 0), canvas.pixel_at(1, 2));
+        assert!(restored.pixels().eq(canvas.pixels()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Region 3x3 at (2, 0) out of bounds for 4x4 canvas")]
+    fn test_cropping_past_the_edge_panics() {
+        Canvas::new(4, 4).crop(2, 0, 3, 3);
+    }
+
+    #[test]
+    fn test_flipping() {
+        let original = numbered_canvas(3, 2);
+        let mut canvas = original.crop(0, 0, 3, 2);
+
+        canvas.flip_horizontal();
+        assert_eq!(canvas.pixel_at(0, 1), original.pixel_at(2, 1));
+        canvas.flip_vertical();
+        assert_eq!(canvas.pixel_at(0, 0), original.pixel_at(2, 1));
+
+        canvas.flip_horizontal();
+        canvas.flip_vertical();
+        assert!(canvas.pixels().eq(original.pixels()));
+    }
+
+    fn mid_gray_byte(options: &ExportOptions) -> String {
+        let mut canvas = Canvas::new(1, 1);
+        canvas.write_pixel(0, 0, Color::GRAY);
+
+        canvas
+            .to_ppm_with(options)
+            .lines()
+            .nth(3)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_gamma_correction_on_export() {
+        let linear = ExportOptions {
+            gamma: 1.0,
+            ..Default::default()
+        };
+
+        assert_eq!(mid_gray_byte(&linear), "128 128 128");
+        assert_eq!(mid_gray_byte(&ExportOptions::default()), "186 186 186");
+        let srgb = ExportOptions {
+            srgb: true,
+            ..Default::default()
+        };
+        assert_eq!(mid_gray_byte(&srgb), "188 188 188");
+    }
+
+    #[test]
+    fn test_exposure_scales_before_gamma() {
+        let options = ExportOptions {
+            gamma: 1.0,
+            exposure: 0.5,
+            srgb: false,
+        };
+
+        assert_eq!(mid_gray_byte(&options), "64 64 64");
+    }
+
+    #[test]
+    #[should_panic(expected = "Export gamma must be positive, got 0")]
+    fn test_zero_gamma_is_rejected() {
+        let options = ExportOptions {
+            gamma: 0.0,
+            ..Default::default()
+        };
+
+        mid_gray_byte(&options);
+    }
+
+    #[test]
+    #[should_panic(expected = "Export gamma must be positive, got -2.2")]
+    #[cfg(feature = "png")]
+    fn test_negative_gamma_is_rejected_by_png_export() {
+        let options = ExportOptions {
+            gamma: -2.2,
+            ..Default::default()
+        };
+
+        let _ = Canvas::new(1, 1).write_png_with(Vec::new(), &options);
+    }
+
+    #[test]
+    fn test_srgb_export_ignores_gamma() {
+        let options = ExportOptions {
+            gamma: 0.0,
+            srgb: true,
+            ..Default::default()
+        };
+
+        assert_eq!(mid_gray_byte(&options), "188 188 188");
+    }
+
+    #[test]
+    fn test_tone_mapping_works_from_any_storage() {
+        let export = |storage| {
+            let mut canvas = Canvas::with_storage(2, 2, storage);
+            canvas.write_pixel(0, 0, Color::GRAY);
+            canvas.write_pixel(1, 1, Color::WHITE);
+            canvas.to_ppm_with(&ExportOptions::default())
+        };
+
+        let expected = export(CanvasStorage::F64);
+        assert!(expected.ends_with("186 186 186 0 0 0\n0 0 0 255 255 255\n"));
+        assert_eq!(export(CanvasStorage::F32), expected);
+        assert_eq!(export(CanvasStorage::Rgbe8), expected);
+    }
+
+    #[test]
+    fn test_linear_export_matches_plain_export() {
+        let mut canvas = Canvas::new(8, 7);
+        for (i, color) in sample_colors().into_iter().take(56).enumerate() {
+            canvas.write_pixel(i % 8, i / 8, color);
+        }
+        let mut plain = Vec::new();
+        PPMEncoder::new(&mut plain).write(&canvas).unwrap();
+        let linear = ExportOptions {
+            gamma: 1.0,
+            ..Default::default()
+        };
+
+        assert_eq!(canvas.to_ppm_with(&linear).as_bytes(), plain);
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn test_png_export_with_gamma() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas.write_pixel(0, 0, Color::GRAY);
+        let mut buffer = Vec::new();
+
+        canvas
+            .write_png_with(&mut buffer, &ExportOptions::default())
+            .unwrap();
+
+        let mut reader = png::Decoder::new(buffer.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(&pixels[..3], &[186, 186, 186]);
+    }
+
+    #[test]
+    fn test_default_storage_is_f64() {
+        assert_eq!(Canvas::new(2, 2).storage(), CanvasStorage::F64);
+        assert_eq!(
+            Canvas::with_storage(2, 2, CanvasStorage::Rgbe8).storage(),
+            CanvasStorage::Rgbe8
+        );
+    }
+
+    #[test]
+    fn test_f64_storage_round_trips_exactly() {
+        for color in sample_colors() {
+            assert_eq!(round_trip(CanvasStorage::F64, color), color);
+        }
+    }
+
+    #[test]
+    fn test_f32_storage_round_trips_within_tolerance() {
+        for color in sample_colors() {
+            let c = round_trip(CanvasStorage::F32, color);
+            let within = |a: f64, b: f64| (a - b).abs() <= b.abs() * f32::EPSILON as f64 / 2.0;
+
+            assert!(within(c.r, color.r), "{:?} -> {:?}", color, c);
+            assert!(within(c.g, color.g), "{:?} -> {:?}", color, c);
+            assert!(within(c.b, color.b), "{:?} -> {:?}", color, c);
+        }
+    }
+
+    #[test]
+    fn test_rgbe8_storage_round_trips_within_tolerance() {
+        for color in sample_colors() {
+            let c = round_trip(CanvasStorage::Rgbe8, color);
+            let tolerance = color.r.max(color.g).max(color.b) / 255.0;
+            let within = |a: f64, b: f64| (a - b).abs() <= tolerance;
+
+            assert!(within(c.r, color.r), "{:?} -> {:?}", color, c);
+            assert!(within(c.g, color.g), "{:?} -> {:?}", color, c);
+            assert!(within(c.b, color.b), "{:?} -> {:?}", color, c);
+        }
+    }
+
+    #[test]
+    fn test_rgbe8_storage_clamps_negative_and_non_finite_channels() {
+        assert_eq!(
+            round_trip(CanvasStorage::Rgbe8, Color::new(-1.0, f64::NAN, -0.5)),
+            Color::new(0.0, 0.0, 0.0)
+        );
+
+        let c = round_trip(CanvasStorage::Rgbe8, Color::new(f64::INFINITY, 1.0, 0.0));
+        assert!(c.r.is_finite() && c.r > 1e30);
+        assert!(c.g.is_finite());
+    }
+
+    #[test]
+    fn test_render_into_f32_canvas_matches_f64_canvas() {
+        let expected = render_default_scene(CanvasStorage::F64);
+        let actual = render_default_scene(CanvasStorage::F32);
+
+        for (a, b) in actual.into_iter().zip(expected) {
+            assert!((a.r - b.r).abs() < 1e-5);
+            assert!((a.g - b.g).abs() < 1e-5);
+            assert!((a.b - b.b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_exporting_from_any_storage() {
+        let encode = |storage| {
+            let mut canvas = Canvas::with_storage(3, 2, storage);
+            canvas.put_pixel(Color::new(1.0, 0.0, 0.0), (0, 0));
+            canvas.put_pixel(Color::new(0.0, 0.5, 0.0), (1, 1));
+            canvas.put_pixel(Color::new(0.0, 0.0, 1.0), (2, 1));
+            let mut buffer = Vec::new();
+            PPMEncoder::new(&mut buffer).write(&canvas).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        let expected = "P3\n3 2\n255\n255 0 0 0 0 0 0 0 0\n0 0 0 0 128 0 0 0 255\n";
+        assert_eq!(encode(CanvasStorage::F64), expected);
+        assert_eq!(encode(CanvasStorage::F32), expected);
+        assert_eq!(encode(CanvasStorage::Rgbe8), expected);
+    }
+
+    #[test]
+    fn test_exporting_converts_compact_storage_a_row_at_a_time() {
+        for storage in [CanvasStorage::F64, CanvasStorage::F32, CanvasStorage::Rgbe8] {
+            let mut canvas = Canvas::with_storage(3, 2, storage);
+            canvas.put_pixel(Color::new(0.0, 0.5, 0.0), (1, 1));
+
+            let row = canvas.row(1);
+
+            assert_eq!(row.len(), 3);
+            assert_eq!(row[1], Color::new(0.0, 0.5, 0.0));
+            assert_eq!(
+                matches!(row, Cow::Borrowed(_)),
+                storage == CanvasStorage::F64
+            );
+        }
+    }
+
+    #[test]
+    fn test_binary_ppm_matches_text_ppm() {
+        let mut canvas = Canvas::new(4, 3);
+        for (i, color) in sample_colors().into_iter().take(12).enumerate() {
+            canvas.write_pixel(i % 4, i / 4, color);
+        }
+        let mut text = Vec::new();
+        PPMEncoder::new(&mut text).write(&canvas).unwrap();
+        let mut binary = Vec::new();
+
+        canvas.write_ppm_binary(&mut binary).unwrap();
+
+        let text = String::from_utf8(text).unwrap();
+        let header = "P6\n4 3\n255\n";
+        assert_eq!(&binary[..header.len()], header.as_bytes());
+        let expected: Vec<u8> = text
+            .lines()
+            .skip(3)
+            .flat_map(str::split_whitespace)
+            .map(|n| n.parse().unwrap())
+            .collect();
+        assert_eq!(&binary[header.len()..], expected);
+    }
+
+    #[test]
+    fn test_streamed_ppm_matches_canvas_export() {
+        let colors: Vec<_> = sample_colors().into_iter().take(20).collect();
+        let mut canvas = Canvas::new(5, 4);
+        for (i, &color) in colors.iter().enumerate() {
+            canvas.write_pixel(i % 5, i / 5, color);
+        }
+        let mut expected = Vec::new();
+        PPMEncoder::new(&mut expected).write(&canvas).unwrap();
+        let mut stream = crate::ppm::PpmStreamWriter::new(Vec::new(), 5, 4).unwrap();
+
+        for row in colors.chunks(5) {
+            stream.write_row(row).unwrap();
+        }
+
+        assert_eq!(stream.finish().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_saving_a_binary_ppm() {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.write_pixel(1, 0, Color::RED);
+        let path = std::env::temp_dir().join(format!("canvas-{}.ppm", std::process::id()));
+
+        canvas.save_ppm(&path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend([0, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn test_png_round_trip() {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.write_pixel(0, 0, Color::RED);
+        canvas.write_pixel(1, 1, Color::new(0.0, 0.5, 0.0));
+        canvas.write_pixel(2, 1, Color::new(1.5, -0.5, 0.2));
+        let mut buffer = Vec::new();
+
+        canvas.write_png(&mut buffer).unwrap();
+
+        let mut reader = png::Decoder::new(buffer.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        assert_eq!(info.bit_depth, png::BitDepth::Eight);
+        let mut ppm = Vec::new();
+        canvas.write_ppm_binary(&mut ppm).unwrap();
+        assert_eq!(&pixels[..info.buffer_size()], &ppm[ppm.len() - 18..]);
+        assert_eq!(&pixels[15..18], &[255, 0, 51]);
+    }
+
+    #[test]
+    fn test_reading_a_file_with_the_wrong_magic_number() {
+        let ppm = "P32\n1 1\n255\n0 0 0\n";
+
+        let result = Canvas::from_ppm(ppm.as_bytes());
+
+        assert!(matches!(result, Err(PpmParseError::BadMagic { found }) if found == "P32"));
+    }
+
+    #[test]
+    fn test_reading_a_ppm_returns_a_canvas_of_the_right_size() {
+        let ppm = "P3\n10 2\n255\n".to_string() + &"0 0 0 ".repeat(20);
+
+        let canvas = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+
+        assert_eq!((canvas.width(), canvas.height()), (10, 2));
+    }
+
+    #[test]
+    fn test_reading_pixel_data_from_a_ppm() {
+        let ppm = "P3
+4 3
+255
+255 127 0  0 127 255  127 255 0  255 255 255
+0 0 0  255 0 0  0 255 0  0 0 255
+255 255 0  0 255 255  255 0 255  127 127 127
+";
+
+        let canvas = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+
+        let expected = [
+            (0, 0, Color::new(1.0, 127.0 / 255.0, 0.0)),
+            (1, 0, Color::new(0.0, 127.0 / 255.0, 1.0)),
+            (3, 0, Color::WHITE),
+            (1, 1, Color::RED),
+            (3, 1, Color::BLUE),
+            (2, 2, Color::new(1.0, 0.0, 1.0)),
+        ];
+        for (x, y, color) in expected {
+            assert_eq!(canvas.pixel_at(x, y), color, "pixel ({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_ppm_parsing_ignores_comment_lines() {
+        let ppm = "P3
+# this is a comment
+2 1
+# this, too
+255
+# another comment
+255 255 255
+# oh, no, comments in the pixel data!
+255 0 255
+";
+
+        let canvas = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+
+        assert_eq!(canvas.pixel_at(0, 0), Color::WHITE);
+        assert_eq!(canvas.pixel_at(1, 0), Color::new(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_ppm_parsing_allows_rgb_triples_to_span_lines() {
+        let ppm = "P3\n1 1\n255\n51\n153\n\n204\n";
+
+        let canvas = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+
+        assert_eq!(canvas.pixel_at(0, 0), Color::new(0.2, 0.6, 0.8));
+    }
+
+    #[test]
+    fn test_ppm_parsing_respects_the_scale_setting() {
+        let ppm = "P3\n2 2\n100\n100 100 100  50 50 50\n75 50 25  0 0 0\n";
+
+        let canvas = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+
+        assert_eq!(canvas.pixel_at(0, 1), Color::new(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    fn test_ppm_parsing_errors() {
+        let parse = |ppm: &str| Canvas::from_ppm(ppm.as_bytes()).err().unwrap();
+
+        assert!(matches!(
+            parse("P3\n1 1\n100\n0 101 0\n"),
+            PpmParseError::ValueAboveMax {
+                value: 101,
+                max: 100
+            }
+        ));
+        assert!(matches!(
+            parse("P3\n2 1\n255\n0 0 0 0 0\n"),
+            PpmParseError::UnexpectedEnd
+        ));
+        assert!(matches!(
+            parse("P3\n1 1\n255\n0 x 0\n"),
+            PpmParseError::InvalidNumber { token } if token == "x"
+        ));
+        assert!(matches!(
+            parse("P3\n1 1\n0\n0 0 0\n"),
+            PpmParseError::InvalidMaxValue { value: 0 }
+        ));
+        assert_eq!(
+            parse("P3\n1 1\n100\n0 101 0\n").to_string(),
+            "color value 101 exceeds the maximum 100"
+        );
+    }
+
+    #[test]
+    fn test_a_truncated_ppm_with_a_huge_header_is_an_error() {
+        for ppm in [
+            "P3\n4000000000 4000000000\n255\n0 0 0\n",
+            "P3\n100000 100000\n255\n0 0 0\n",
+            "P6\n100000 100000\n255\n\x00\x00\x00",
+        ] {
+            let err = Canvas::from_ppm(ppm.as_bytes()).err().unwrap();
+
+            assert!(matches!(err, PpmParseError::UnexpectedEnd), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn test_binary_ppm_round_trip() {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.write_pixel(0, 0, Color::RED);
+        canvas.write_pixel(2, 1, Color::new(0.2, 0.4, 1.0));
+        let mut buffer = Vec::new();
+        canvas.write_ppm_binary(&mut buffer).unwrap();
+
+        let read = Canvas::from_ppm(buffer.as_slice()).unwrap();
+
+        assert!(read.pixels().eq(canvas.pixels()));
+    }
+
+    #[test]
+    fn test_reading_a_sixteen_bit_binary_ppm() {
+        let mut ppm = b"P6 1 1 # comment\n65535\n".to_vec();
+        ppm.extend([0xff, 0xff, 0x80, 0x00, 0x00, 0x00]);
+
+        let canvas = Canvas::from_ppm(ppm.as_slice()).unwrap();
+
+        assert_eq!(
+            canvas.pixel_at(0, 0),
+            Color::new(1.0, 32768.0 / 65535.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_compact_storage_uses_less_memory_per_pixel() {
+        use std::mem::size_of;
+
+        assert_eq!(size_of::<Color>(), 24);
+        assert_eq!(size_of::<F32Pixel>(), 12);
+        assert_eq!(size_of::<Rgbe8Pixel>(), 4);
     }
 }
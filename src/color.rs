@@ -1,6 +1,32 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
 use std::ops::{Add, Mul, Sub};
 
-use crate::ppm::RGB;
+use crate::approx::ApproxEq;
+use crate::ppm::{channel_to_u8, RGB};
+use crate::tuple::Elem;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorParseError {
+    InvalidLength { found: usize },
+    InvalidDigit { digit: char, at: usize },
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorParseError::InvalidLength { found } => {
+                write!(f, "expected 3 or 6 hex digits, found {}", found)
+            }
+            ColorParseError::InvalidDigit { digit, at } => {
+                write!(f, "invalid hex digit `{}` at offset {}", digit, at)
+            }
+        }
+    }
+}
+
+impl Error for ColorParseError {}
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Color {
@@ -10,9 +36,124 @@ pub struct Color {
 }
 
 impl Color {
-    pub fn new(r: f64, g: f64, b: f64) -> Self {
+    pub const BLACK: Color = Color::new(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::new(1.0, 1.0, 1.0);
+    pub const GRAY: Color = Color::new(0.5, 0.5, 0.5);
+    pub const RED: Color = Color::new(1.0, 0.0, 0.0);
+    pub const GREEN: Color = Color::new(0.0, 1.0, 0.0);
+    pub const BLUE: Color = Color::new(0.0, 0.0, 1.0);
+
+    pub const fn new(r: f64, g: f64, b: f64) -> Self {
         Color { r, g, b }
     }
+
+    /// Parses `rrggbb` or the short `rgb` form, with or without a leading
+    /// `#`. Offsets in errors count from the start of `s`.
+    pub fn from_hex(s: &str) -> Result<Color, ColorParseError> {
+        let start = usize::from(s.starts_with('#'));
+        let digits = &s[start..];
+
+        let mut values = Vec::with_capacity(6);
+        for (at, c) in digits.char_indices() {
+            let digit = c.to_digit(16).ok_or(ColorParseError::InvalidDigit {
+                digit: c,
+                at: start + at,
+            })?;
+            values.push(digit);
+        }
+
+        let channels = match values.len() {
+            3 => values.iter().map(|d| d * 17).collect::<Vec<_>>(),
+            6 => values.chunks(2).map(|d| d[0] * 16 + d[1]).collect(),
+            found => return Err(ColorParseError::InvalidLength { found }),
+        };
+        let channel = |i: usize| channels[i] as f64 / 255.0;
+
+        Ok(Color::new(channel(0), channel(1), channel(2)))
+    }
+
+    /// Hue in degrees, wrapped into `0..360`; saturation and value are
+    /// clamped to `0..=1`.
+    pub fn from_hsv(h: f64, s: f64, v: f64) -> Self {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let chroma = v * s;
+        Color::from_hue_chroma(h, chroma, v - chroma)
+    }
+
+    /// Returns `(h, s, v)` with the hue in degrees; grays have hue 0.
+    pub fn to_hsv(&self) -> (f64, f64, f64) {
+        let (max, min) = self.extremes();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (self.hue(), s, max)
+    }
+
+    /// Like [`Color::from_hsv`], with lightness in place of value.
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> Self {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Color::from_hue_chroma(h, chroma, l - chroma / 2.0)
+    }
+
+    /// Returns `(h, s, l)` with the hue in degrees; grays have hue 0.
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let (max, min) = self.extremes();
+        let l = (max + min) / 2.0;
+        let s = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (self.hue(), s, l)
+    }
+
+    fn from_hue_chroma(h: f64, chroma: f64, m: f64) -> Self {
+        let sector = h.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Color::new(r + m, g + m, b + m)
+    }
+
+    fn extremes(&self) -> (f64, f64) {
+        (
+            self.r.max(self.g).max(self.b),
+            self.r.min(self.g).min(self.b),
+        )
+    }
+
+    fn hue(&self) -> f64 {
+        let (max, min) = self.extremes();
+        let delta = max - min;
+        if delta == 0.0 {
+            return 0.0;
+        }
+
+        let sector = if max == self.r {
+            ((self.g - self.b) / delta).rem_euclid(6.0)
+        } else if max == self.g {
+            (self.b - self.r) / delta + 2.0
+        } else {
+            (self.r - self.g) / delta + 4.0
+        };
+        sector * 60.0
+    }
+
+    /// Formats as `#rrggbb`, clamping channels to `0..=1` first.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r(), self.g(), self.b())
+    }
+}
+
+impl ApproxEq for Color {
+    fn approx_elements(&self) -> Cow<'_, [Elem]> {
+        Cow::Owned(vec![self.r as Elem, self.g as Elem, self.b as Elem])
+    }
 }
 
 impl Add for Color {
@@ -49,25 +190,24 @@ impl Mul for Color {
 
 impl RGB for Color {
     fn r(&self) -> u8 {
-        clamp_to_u8(self.r)
+        channel_to_u8(self.r)
     }
 
     fn g(&self) -> u8 {
-        clamp_to_u8(self.g)
+        channel_to_u8(self.g)
     }
 
     fn b(&self) -> u8 {
-        clamp_to_u8(self.b)
+        channel_to_u8(self.b)
     }
 }
 
-fn clamp_to_u8(n: f64) -> u8 {
-    (n * 255.0).clamp(0.0, 255.0).round() as u8
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::approx::tolerance;
+    use crate::assert_approx_eq;
+    use crate::test_rng::Lcg;
 
     const EPSILON: f64 = 1e-6;
 
@@ -113,8 +253,122 @@ mod tests {
 
         let c3 = c1 * c2;
 
-        assert!(equal(c3.r, 0.9));
-        assert!(equal(c3.g, 0.2));
-        assert!(equal(c3.b, 0.04));
+        assert_approx_eq!(c3, Color::new(0.9, 0.2, 0.04), tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_parsing_hex_colors() {
+        let orange = Color::new(1.0, 136.0 / 255.0, 0.0);
+
+        assert_eq!(Color::from_hex("#ff8800"), Ok(orange));
+        assert_eq!(Color::from_hex("ff8800"), Ok(orange));
+        assert_eq!(Color::from_hex("#f80"), Ok(orange));
+        assert_eq!(Color::from_hex("#FF8800"), Ok(orange));
+    }
+
+    #[test]
+    fn test_parsing_invalid_hex_colors() {
+        assert_eq!(
+            Color::from_hex("#ff88"),
+            Err(ColorParseError::InvalidLength { found: 4 })
+        );
+        assert_eq!(
+            Color::from_hex(""),
+            Err(ColorParseError::InvalidLength { found: 0 })
+        );
+        assert_eq!(
+            Color::from_hex("#ff8g00"),
+            Err(ColorParseError::InvalidDigit { digit: 'g', at: 4 })
+        );
+        assert_eq!(
+            Color::from_hex("#ff8g00").unwrap_err().to_string(),
+            "invalid hex digit `g` at offset 4"
+        );
+        assert!(Color::from_hex("#ff8é0").is_err());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        for hex in ["#000000", "#ffffff", "#ff8800", "#123abc"] {
+            assert_eq!(Color::from_hex(hex).unwrap().to_hex(), hex);
+        }
+
+        let c = Color::new(0.3, 0.6, 0.9);
+        assert_approx_eq!(Color::from_hex(&c.to_hex()).unwrap(), c, 1.0 / 255.0);
+        assert_eq!(Color::new(1.5, -0.2, 0.5).to_hex(), "#ff0080");
+    }
+
+    #[test]
+    fn test_hsv_hue_points() {
+        let points = [
+            (0.0, Color::RED),
+            (60.0, Color::new(1.0, 1.0, 0.0)),
+            (120.0, Color::GREEN),
+            (180.0, Color::new(0.0, 1.0, 1.0)),
+            (240.0, Color::BLUE),
+            (300.0, Color::new(1.0, 0.0, 1.0)),
+        ];
+
+        for (h, color) in points {
+            assert_approx_eq!(Color::from_hsv(h, 1.0, 1.0), color, tolerance(1e-12));
+            assert_approx_eq!(Color::from_hsl(h, 1.0, 0.5), color, tolerance(1e-12));
+            let (hue, s, v) = color.to_hsv();
+            assert!((hue - h).abs() < 1e-12 && s == 1.0 && v == 1.0);
+        }
+    }
+
+    #[test]
+    fn test_hue_wraps_and_components_clamp() {
+        assert_approx_eq!(Color::from_hsv(-120.0, 1.0, 1.0), Color::BLUE);
+        assert_approx_eq!(Color::from_hsv(480.0, 1.0, 1.0), Color::GREEN);
+        assert_approx_eq!(Color::from_hsv(0.0, 2.0, 1.5), Color::RED);
+        assert_approx_eq!(Color::from_hsl(0.0, -1.0, 0.5), Color::GRAY);
+    }
+
+    #[test]
+    fn test_gray_round_trips() {
+        let gray = Color::new(0.4, 0.4, 0.4);
+
+        assert_eq!(gray.to_hsv(), (0.0, 0.0, 0.4));
+        assert_eq!(gray.to_hsl(), (0.0, 0.0, 0.4));
+        assert_approx_eq!(Color::from_hsv(0.0, 0.0, 0.4), gray, tolerance(1e-12));
+        assert_approx_eq!(Color::from_hsl(0.0, 0.0, 0.4), gray, tolerance(1e-12));
+    }
+
+    #[test]
+    fn test_random_colors_round_trip() {
+        let mut rng = Lcg::new(17);
+
+        for _ in 0..1000 {
+            let c = Color::new(rng.next_f64(), rng.next_f64(), rng.next_f64());
+
+            let (h, s, v) = c.to_hsv();
+            assert_approx_eq!(Color::from_hsv(h, s, v), c, tolerance(1e-6));
+            let (h, s, l) = c.to_hsl();
+            assert_approx_eq!(Color::from_hsl(h, s, l), c, tolerance(1e-6));
+        }
+    }
+
+    #[test]
+    fn test_named_colors() {
+        assert_eq!(Color::RED + Color::GREEN + Color::BLUE, Color::WHITE);
+        assert_eq!(Color::WHITE * 0.5, Color::GRAY);
+    }
+
+    #[test]
+    fn test_black_and_white() {
+        let c = Color::new(0.3, 0.6, 0.9);
+
+        assert_eq!(c * Color::BLACK, Color::BLACK);
+        assert_eq!(c * Color::WHITE, c);
+        assert_eq!(c + Color::BLACK, c);
+    }
+
+    #[test]
+    fn test_approximately_equal_colors() {
+        let c = Color::new(0.3, 0.6, 0.9);
+
+        assert!(c.approx_eq(&Color::new(0.3 + 1e-8, 0.6, 0.9 - 1e-8), 1e-6));
+        assert!(!c.approx_eq(&Color::new(0.3, 0.6 + 1e-4, 0.9), 1e-6));
     }
 }
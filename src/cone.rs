@@ -0,0 +1,322 @@
+use crate::cylinder::within_cap;
+use crate::intersection::Intersection;
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::roots::solve_quadratic;
+use crate::shape::{Shape, PARALLEL_EPSILON, SURFACE_EPSILON};
+use crate::transform::Transform;
+use crate::tuple::{Elem, Tuple4};
+
+/// A double-napped cone around the y axis with its apex at the origin and a
+/// radius of `|y|` at every height, cut off below `minimum` and above
+/// `maximum` (both exclusive). With `closed` set, the cut ends are capped.
+#[derive(PartialEq)]
+pub struct Cone {
+    pub minimum: Elem,
+    pub maximum: Elem,
+    pub closed: bool,
+    transform: Transform,
+    material: Material,
+}
+
+impl Cone {
+    /// An infinite, open cone.
+    pub fn new() -> Cone {
+        Cone {
+            minimum: Elem::NEG_INFINITY,
+            maximum: Elem::INFINITY,
+            closed: false,
+            transform: Transform::identity(),
+            material: Material::default(),
+        }
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shape for Cone {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let (o, d) = (ray.origin, ray.direction);
+        let mut xs = Vec::new();
+
+        // The wall is solved from the point on the ray nearest the apex,
+        // `n`, rather than from the origin. Far from the apex `c` is the
+        // difference of two large squares, and near it the roots are close
+        // together; the rounding would slide them along the ray and off
+        // the surface.
+        let dd = d.dot(&d);
+        let shift = if dd > 0.0 {
+            -(o - Tuple4::point(0.0, 0.0, 0.0)).dot(&d) / dd
+        } else {
+            0.0
+        };
+        let n = ray.position(shift);
+
+        let a = d.x * d.x - d.y * d.y + d.z * d.z;
+        let b = 2.0 * (n.x * d.x - n.y * d.y + n.z * d.z);
+        let c = n.x * n.x - n.y * n.y + n.z * n.z;
+
+        // A ray parallel to one nappe's slope crosses the other nappe
+        // exactly once. If `b` vanishes too the ray runs along the surface
+        // or through the apex, and counts as a miss. Neither `n` nor the
+        // direction is normalized in object space, so both tests are
+        // relative to their lengths.
+        let nn = n.x * n.x + n.y * n.y + n.z * n.z;
+        let wall = if a.abs() > PARALLEL_EPSILON * dd {
+            solve_quadratic(a, b, c).map_or(Vec::new(), |(t0, t1)| vec![t0, t1])
+        } else if b * b > PARALLEL_EPSILON * 4.0 * nn * dd {
+            vec![-c / b]
+        } else {
+            Vec::new()
+        };
+
+        for t in wall.into_iter().map(|t| t + shift) {
+            let y = o.y + t * d.y;
+            if self.minimum < y && y < self.maximum {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+
+        if self.closed && d.y * d.y > PARALLEL_EPSILON * dd {
+            for cap in [self.minimum, self.maximum] {
+                let t = (cap - o.y) / d.y;
+                if within_cap(ray, t, cap.abs()) {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        xs
+    }
+
+    /// Points strictly inside a cap's disc are on that cap. On the wall the
+    /// normal leans away from the axis, downwards on the upper nappe and
+    /// upwards on the lower one. At the apex it degenerates to zero.
+    fn local_normal_at(&self, p: Tuple4) -> Tuple4 {
+        let dist = p.x * p.x + p.z * p.z;
+
+        if dist < p.y * p.y && p.y >= self.maximum - SURFACE_EPSILON {
+            Tuple4::vector(0.0, 1.0, 0.0)
+        } else if dist < p.y * p.y && p.y <= self.minimum + SURFACE_EPSILON {
+            Tuple4::vector(0.0, -1.0, 0.0)
+        } else {
+            let y = dist.sqrt();
+            let y = if p.y > 0.0 { -y } else { y };
+            Tuple4::vector(p.x, y, p.z)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::tolerance;
+    use crate::matrix::Matrix4x4;
+    use crate::tuple::consts::SQRT_2;
+
+    fn ray(origin: (Elem, Elem, Elem), direction: (Elem, Elem, Elem)) -> Ray {
+        Ray::new(
+            Tuple4::point(origin.0, origin.1, origin.2),
+            Tuple4::vector(direction.0, direction.1, direction.2).normalize(),
+        )
+    }
+
+    fn truncated(closed: bool) -> Cone {
+        Cone {
+            minimum: -0.5,
+            maximum: 0.5,
+            closed,
+            ..Cone::new()
+        }
+    }
+
+    #[test]
+    fn test_intersecting_a_cone_with_a_ray() {
+        let shape = Cone::new();
+        let table = [
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 5.0, 5.0),
+            ((1.0, 1.0, -5.0), (-0.5, -1.0, 1.0), 4.55006, 49.44994),
+        ];
+
+        for (origin, direction, t0, t1) in table {
+            let xs = shape.local_intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), 2, "ray from {:?}", origin);
+            assert!(
+                (xs[0].t - t0).abs() < tolerance(1e-4),
+                "{} != {}",
+                xs[0].t,
+                t0
+            );
+            assert!(
+                (xs[1].t - t1).abs() < tolerance(1e-4),
+                "{} != {}",
+                xs[1].t,
+                t1
+            );
+        }
+    }
+
+    /// The book's ray along (1, 1, 1) grazes the cone, so its discriminant
+    /// is zero. Normalizing the direction leaves it a rounding error either
+    /// side of zero under `f32`; left unnormalized it never goes negative,
+    /// though the two roots can still come out a rounding error apart.
+    #[test]
+    fn test_a_ray_tangent_to_a_cone_hits_it_twice_at_the_same_point() {
+        let shape = Cone::new();
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(1.0, 1.0, 1.0));
+
+        let xs = shape.local_intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        for x in &xs {
+            assert!((x.t - 5.0).abs() < tolerance(1e-9), "{}", x.t);
+        }
+    }
+
+    /// The book expects 0.35355 here, which comes from dividing by `2b`
+    /// where `b` already carries the factor of two. The ray really meets
+    /// the cone at z = -0.5, a distance of √2 / 2 along it.
+    #[test]
+    fn test_intersecting_a_cone_with_a_ray_parallel_to_one_of_its_halves() {
+        let shape = Cone::new();
+
+        let xs = shape.local_intersect(&ray((0.0, 0.0, -1.0), (0.0, 1.0, 1.0)));
+
+        assert_eq!(xs.len(), 1);
+        assert!((xs[0].t - SQRT_2 / 2.0).abs() < tolerance(1e-5));
+    }
+
+    /// From this far off, the two hits either side of the apex come from a
+    /// discriminant that's the difference of two numbers near 360000.
+    #[test]
+    fn test_a_distant_ray_passing_close_to_the_apex() {
+        let shape = Cone::new();
+
+        let xs = shape.local_intersect(&ray((0.005, 0.01, 300.0), (0.0, 0.0, -1.0)));
+
+        assert_eq!(xs.len(), 2);
+        let z = (0.01 as Elem * 0.01 - 0.005 * 0.005).sqrt();
+        assert!(
+            (xs[0].t - (300.0 - z)).abs() < tolerance(1e-9),
+            "{}",
+            xs[0].t
+        );
+        assert!(
+            (xs[1].t - (300.0 + z)).abs() < tolerance(1e-9),
+            "{}",
+            xs[1].t
+        );
+    }
+
+    #[test]
+    fn test_a_ray_along_the_surface_of_a_cone_misses() {
+        let shape = Cone::new();
+
+        let xs = shape.local_intersect(&ray((0.0, -1.0, -1.0), (0.0, 1.0, 1.0)));
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn test_intersecting_a_cones_end_caps() {
+        let shape = truncated(true);
+        let table = [
+            ((0.0, 0.0, -5.0), (0.0, 1.0, 0.0), 0),
+            ((0.0, 0.0, -0.25), (0.0, 1.0, 1.0), 2),
+            ((0.0, 0.0, -0.25), (0.0, 1.0, 0.0), 4),
+        ];
+
+        for (origin, direction, count) in table {
+            let xs = shape.local_intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), count, "ray from {:?}", origin);
+        }
+    }
+
+    #[test]
+    fn test_an_open_cone_has_no_caps() {
+        let shape = truncated(false);
+
+        let xs = shape.local_intersect(&ray((0.0, 0.0, -0.25), (0.0, 1.0, 0.0)));
+
+        assert_eq!(xs.len(), 2);
+    }
+
+    #[test]
+    fn test_computing_the_normal_vector_on_a_cone() {
+        let shape = Cone::new();
+        let table = [
+            ((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)),
+            ((1.0, 1.0, 1.0), (1.0, -SQRT_2, 1.0)),
+            ((-1.0, -1.0, 0.0), (-1.0, 1.0, 0.0)),
+        ];
+
+        for (p, n) in table {
+            let normal = shape.local_normal_at(Tuple4::point(p.0, p.1, p.2));
+
+            assert_eq!(normal, Tuple4::vector(n.0, n.1, n.2), "point {:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_the_normal_on_a_cones_end_caps() {
+        let shape = truncated(true);
+        let table = [
+            ((0.25, 0.5, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, -0.5, -0.25), (0.0, -1.0, 0.0)),
+            ((0.5, 0.5, 0.0), (0.5, -0.5, 0.0)),
+        ];
+
+        for (p, n) in table {
+            let normal = shape.local_normal_at(Tuple4::point(p.0, p.1, p.2));
+
+            assert_eq!(normal, Tuple4::vector(n.0, n.1, n.2), "point {:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_intersecting_a_scaled_cone() {
+        let mut shape = truncated(true);
+        shape.set_transform(Matrix4x4::scaling(2000.0, 2000.0, 2000.0));
+        let table = [
+            // Across the wall where its radius is 500.
+            ((0.0, 500.0, -1e4), (0.0, 0.0, 1.0), vec![9500.0, 10500.0]),
+            // Down through both caps and both nappes.
+            (
+                (250.0, 1e4, 0.0),
+                (0.0, -1.0, 0.0),
+                vec![9000.0, 9750.0, 10250.0, 11000.0],
+            ),
+        ];
+
+        for (origin, direction, ts) in table {
+            let xs = shape.intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), ts.len(), "ray from {:?}", origin);
+            for (x, t) in xs.iter().zip(ts) {
+                assert!((x.t - t).abs() / t < tolerance(1e-9), "{} != {}", x.t, t);
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::intersection::Intersection;
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::transform::Transform;
+use crate::tuple::{Elem, Tuple4};
+
+/// The axis-aligned cube spanning -1 to 1 on every axis.
+#[derive(PartialEq)]
+pub struct Cube {
+    transform: Transform,
+    material: Material,
+}
+
+impl Cube {
+    pub fn new() -> Cube {
+        Cube {
+            transform: Transform::identity(),
+            material: Material::default(),
+        }
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The range of `t` for which `origin + direction * t` lies between the
+/// planes at -1 and 1, or `None` if it never does. A zero direction is
+/// either inside the slab for every `t` or outside for every `t`, which
+/// keeps `0 / 0` out of the picture.
+fn check_axis(origin: Elem, direction: Elem) -> Option<(Elem, Elem)> {
+    if direction == 0.0 {
+        return (-1.0..=1.0)
+            .contains(&origin)
+            .then_some((Elem::NEG_INFINITY, Elem::INFINITY));
+    }
+
+    let t1 = (-1.0 - origin) / direction;
+    let t2 = (1.0 - origin) / direction;
+    Some((t1.min(t2), t1.max(t2)))
+}
+
+impl Shape for Cube {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let (o, d) = (ray.origin, ray.direction);
+        let slabs = [
+            check_axis(o.x, d.x),
+            check_axis(o.y, d.y),
+            check_axis(o.z, d.z),
+        ];
+
+        let mut tmin = Elem::NEG_INFINITY;
+        let mut tmax = Elem::INFINITY;
+        for slab in slabs {
+            let Some((t0, t1)) = slab else {
+                return Vec::new();
+            };
+            tmin = tmin.max(t0);
+            tmax = tmax.min(t1);
+        }
+
+        if tmin > tmax || !tmin.is_finite() || !tmax.is_finite() {
+            return Vec::new();
+        }
+        vec![Intersection::new(tmin, self), Intersection::new(tmax, self)]
+    }
+
+    /// Faces along x win ties over y, and y over z, so edges and corners get
+    /// a well-defined normal.
+    fn local_normal_at(&self, p: Tuple4) -> Tuple4 {
+        let (x, y, z) = (p.x.abs(), p.y.abs(), p.z.abs());
+
+        if x >= y && x >= z {
+            Tuple4::vector(p.x, 0.0, 0.0)
+        } else if y >= z {
+            Tuple4::vector(0.0, p.y, 0.0)
+        } else {
+            Tuple4::vector(0.0, 0.0, p.z)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ray(origin: (Elem, Elem, Elem), direction: (Elem, Elem, Elem)) -> Ray {
+        Ray::new(
+            Tuple4::point(origin.0, origin.1, origin.2),
+            Tuple4::vector(direction.0, direction.1, direction.2),
+        )
+    }
+
+    #[test]
+    fn test_a_ray_intersects_a_cube() {
+        let c = Cube::new();
+        let table = [
+            ((5.0, 0.5, 0.0), (-1.0, 0.0, 0.0), 4.0, 6.0),
+            ((-5.0, 0.5, 0.0), (1.0, 0.0, 0.0), 4.0, 6.0),
+            ((0.5, 5.0, 0.0), (0.0, -1.0, 0.0), 4.0, 6.0),
+            ((0.5, -5.0, 0.0), (0.0, 1.0, 0.0), 4.0, 6.0),
+            ((0.5, 0.0, 5.0), (0.0, 0.0, -1.0), 4.0, 6.0),
+            ((0.5, 0.0, -5.0), (0.0, 0.0, 1.0), 4.0, 6.0),
+            ((0.0, 0.5, 0.0), (0.0, 0.0, 1.0), -1.0, 1.0),
+        ];
+
+        for (origin, direction, t1, t2) in table {
+            let xs = c.local_intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), 2, "ray from {:?}", origin);
+            assert_eq!((xs[0].t, xs[1].t), (t1, t2), "ray from {:?}", origin);
+        }
+    }
+
+    #[test]
+    fn test_a_ray_misses_a_cube() {
+        let c = Cube::new();
+        let table = [
+            ((-2.0, 0.0, 0.0), (0.2673, 0.5345, 0.8018)),
+            ((0.0, -2.0, 0.0), (0.8018, 0.2673, 0.5345)),
+            ((0.0, 0.0, -2.0), (0.5345, 0.8018, 0.2673)),
+            ((2.0, 0.0, 2.0), (0.0, 0.0, -1.0)),
+            ((0.0, 2.0, 2.0), (0.0, -1.0, 0.0)),
+            ((2.0, 2.0, 0.0), (-1.0, 0.0, 0.0)),
+        ];
+
+        for (origin, direction) in table {
+            let xs = c.local_intersect(&ray(origin, direction));
+
+            assert!(xs.is_empty(), "ray from {:?}", origin);
+        }
+    }
+
+    #[test]
+    fn test_rays_along_a_face_plane_do_not_produce_nan() {
+        let c = Cube::new();
+
+        let grazing = c.local_intersect(&ray((1.0, 0.0, -5.0), (0.0, 0.0, 1.0)));
+        let outside = c.local_intersect(&ray((1.5, 0.0, -5.0), (0.0, 0.0, 1.0)));
+        let still = c.local_intersect(&ray((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)));
+
+        assert_eq!(grazing.len(), 2);
+        assert_eq!((grazing[0].t, grazing[1].t), (4.0, 6.0));
+        assert!(outside.is_empty());
+        assert!(still.is_empty());
+    }
+
+    #[test]
+    fn test_the_normal_on_the_surface_of_a_cube() {
+        let c = Cube::new();
+        let table = [
+            ((1.0, 0.5, -0.8), (1.0, 0.0, 0.0)),
+            ((-1.0, -0.2, 0.9), (-1.0, 0.0, 0.0)),
+            ((-0.4, 1.0, -0.1), (0.0, 1.0, 0.0)),
+            ((0.3, -1.0, -0.7), (0.0, -1.0, 0.0)),
+            ((-0.6, 0.3, 1.0), (0.0, 0.0, 1.0)),
+            ((0.4, 0.4, -1.0), (0.0, 0.0, -1.0)),
+            ((1.0, 1.0, 1.0), (1.0, 0.0, 0.0)),
+            ((-1.0, -1.0, -1.0), (-1.0, 0.0, 0.0)),
+            ((0.5, 1.0, -1.0), (0.0, 1.0, 0.0)),
+        ];
+
+        for (p, n) in table {
+            let normal = c.local_normal_at(Tuple4::point(p.0, p.1, p.2));
+
+            assert_eq!(normal, Tuple4::vector(n.0, n.1, n.2), "point {:?}", p);
+        }
+    }
+}
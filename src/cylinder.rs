@@ -0,0 +1,291 @@
+use crate::intersection::Intersection;
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::roots::solve_quadratic;
+use crate::shape::{Shape, PARALLEL_EPSILON, SURFACE_EPSILON};
+use crate::transform::Transform;
+use crate::tuple::{Elem, Tuple4};
+
+/// A cylinder of radius 1 around the y axis, cut off below `minimum` and
+/// above `maximum` (both exclusive). With `closed` set, the cut ends are
+/// capped.
+#[derive(PartialEq)]
+pub struct Cylinder {
+    pub minimum: Elem,
+    pub maximum: Elem,
+    pub closed: bool,
+    transform: Transform,
+    material: Material,
+}
+
+impl Cylinder {
+    /// An infinite, open cylinder.
+    pub fn new() -> Cylinder {
+        Cylinder {
+            minimum: Elem::NEG_INFINITY,
+            maximum: Elem::INFINITY,
+            closed: false,
+            transform: Transform::identity(),
+            material: Material::default(),
+        }
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the ray at `t` lies within a cap of the given radius around the
+/// y axis, rim included give or take rounding.
+pub(crate) fn within_cap(ray: &Ray, t: Elem, radius: Elem) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    x * x + z * z <= radius * radius + SURFACE_EPSILON
+}
+
+impl Shape for Cylinder {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let (o, d) = (ray.origin, ray.direction);
+        let mut xs = Vec::new();
+
+        // A ray parallel to the axis never crosses the wall, and the
+        // quadratic would degenerate. The direction isn't normalized in
+        // object space, so parallel is judged relative to its length.
+        let dd = d.dot(&d);
+        let a = d.x * d.x + d.z * d.z;
+        if a > PARALLEL_EPSILON * dd {
+            let b = 2.0 * (o.x * d.x + o.z * d.z);
+            let c = o.x * o.x + o.z * o.z - 1.0;
+
+            if let Some((t0, t1)) = solve_quadratic(a, b, c) {
+                for t in [t0, t1] {
+                    let y = o.y + t * d.y;
+                    if self.minimum < y && y < self.maximum {
+                        xs.push(Intersection::new(t, self));
+                    }
+                }
+            }
+        }
+
+        if self.closed && d.y * d.y > PARALLEL_EPSILON * dd {
+            for cap in [self.minimum, self.maximum] {
+                let t = (cap - o.y) / d.y;
+                if within_cap(ray, t, 1.0) {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        xs
+    }
+
+    /// Points inside the unit disc and at a cap's height are on that cap;
+    /// everything else, including the rim itself, is on the wall.
+    fn local_normal_at(&self, p: Tuple4) -> Tuple4 {
+        let dist = p.x * p.x + p.z * p.z;
+
+        if dist < 1.0 && p.y >= self.maximum - SURFACE_EPSILON {
+            Tuple4::vector(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && p.y <= self.minimum + SURFACE_EPSILON {
+            Tuple4::vector(0.0, -1.0, 0.0)
+        } else {
+            Tuple4::vector(p.x, 0.0, p.z)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::tolerance;
+    use crate::matrix::Matrix4x4;
+
+    fn ray(origin: (Elem, Elem, Elem), direction: (Elem, Elem, Elem)) -> Ray {
+        Ray::new(
+            Tuple4::point(origin.0, origin.1, origin.2),
+            Tuple4::vector(direction.0, direction.1, direction.2).normalize(),
+        )
+    }
+
+    fn truncated(closed: bool) -> Cylinder {
+        Cylinder {
+            minimum: 1.0,
+            maximum: 2.0,
+            closed,
+            ..Cylinder::new()
+        }
+    }
+
+    #[test]
+    fn test_a_ray_misses_a_cylinder() {
+        let cyl = Cylinder::new();
+        let table = [
+            ((1.0, 0.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 0.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 0.0, -5.0), (1.0, 1.0, 1.0)),
+        ];
+
+        for (origin, direction) in table {
+            let xs = cyl.local_intersect(&ray(origin, direction));
+
+            assert!(xs.is_empty(), "ray from {:?}", origin);
+        }
+    }
+
+    #[test]
+    fn test_a_ray_strikes_a_cylinder() {
+        let cyl = Cylinder::new();
+        let table = [
+            ((1.0, 0.0, -5.0), (0.0, 0.0, 1.0), 5.0, 5.0),
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 4.0, 6.0),
+            ((0.5, 0.0, -5.0), (0.1, 1.0, 1.0), 6.80798, 7.08872),
+        ];
+
+        for (origin, direction, t0, t1) in table {
+            let xs = cyl.local_intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), 2, "ray from {:?}", origin);
+            assert!(
+                (xs[0].t - t0).abs() < tolerance(1e-5),
+                "{} != {}",
+                xs[0].t,
+                t0
+            );
+            assert!(
+                (xs[1].t - t1).abs() < tolerance(1e-5),
+                "{} != {}",
+                xs[1].t,
+                t1
+            );
+        }
+    }
+
+    #[test]
+    fn test_normal_vector_on_a_cylinder() {
+        let cyl = Cylinder::new();
+        let table = [
+            ((1.0, 0.0, 0.0), (1.0, 0.0, 0.0)),
+            ((0.0, 5.0, -1.0), (0.0, 0.0, -1.0)),
+            ((0.0, -2.0, 1.0), (0.0, 0.0, 1.0)),
+            ((-1.0, 1.0, 0.0), (-1.0, 0.0, 0.0)),
+        ];
+
+        for (p, n) in table {
+            let normal = cyl.local_normal_at(Tuple4::point(p.0, p.1, p.2));
+
+            assert_eq!(normal, Tuple4::vector(n.0, n.1, n.2), "point {:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_the_default_cylinder_is_infinite_and_open() {
+        let cyl = Cylinder::new();
+
+        assert_eq!(cyl.minimum, Elem::NEG_INFINITY);
+        assert_eq!(cyl.maximum, Elem::INFINITY);
+        assert!(!cyl.closed);
+    }
+
+    #[test]
+    fn test_intersecting_a_constrained_cylinder() {
+        let cyl = truncated(false);
+        let table = [
+            ((0.0, 1.5, 0.0), (0.1, 1.0, 0.0), 0),
+            ((0.0, 3.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 2.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 1.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 1.5, -2.0), (0.0, 0.0, 1.0), 2),
+        ];
+
+        for (origin, direction, count) in table {
+            let xs = cyl.local_intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), count, "ray from {:?}", origin);
+        }
+    }
+
+    #[test]
+    fn test_intersecting_the_caps_of_a_closed_cylinder() {
+        let cyl = truncated(true);
+        let table = [
+            ((0.0, 3.0, 0.0), (0.0, -1.0, 0.0), 2),
+            ((0.0, 3.0, -2.0), (0.0, -1.0, 2.0), 2),
+            ((0.0, 4.0, -2.0), (0.0, -1.0, 1.0), 2),
+            ((0.0, 0.0, -2.0), (0.0, 1.0, 2.0), 2),
+            ((0.0, -1.0, -2.0), (0.0, 1.0, 1.0), 2),
+        ];
+
+        for (origin, direction, count) in table {
+            let xs = cyl.local_intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), count, "ray from {:?}", origin);
+        }
+    }
+
+    #[test]
+    fn test_normal_vector_on_a_cylinders_end_caps() {
+        let cyl = truncated(true);
+        let table = [
+            ((0.0, 1.0, 0.0), (0.0, -1.0, 0.0)),
+            ((0.5, 1.0, 0.0), (0.0, -1.0, 0.0)),
+            ((0.0, 1.0, 0.5), (0.0, -1.0, 0.0)),
+            ((0.0, 2.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.5, 2.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 2.0, 0.5), (0.0, 1.0, 0.0)),
+        ];
+
+        for (p, n) in table {
+            let normal = cyl.local_normal_at(Tuple4::point(p.0, p.1, p.2));
+
+            assert_eq!(normal, Tuple4::vector(n.0, n.1, n.2), "point {:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_the_rim_of_a_cap_belongs_to_the_wall() {
+        let cyl = truncated(true);
+
+        let normal = cyl.local_normal_at(Tuple4::point(1.0, 2.0, 0.0));
+
+        assert_eq!(normal, Tuple4::vector(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_intersecting_a_scaled_cylinder() {
+        let mut cyl = truncated(true);
+        cyl.set_transform(Matrix4x4::scaling(2000.0, 2000.0, 2000.0));
+        let table = [
+            // Through the wall at z = ±2000.
+            ((0.0, 3000.0, -1e4), (0.0, 0.0, 1.0), 8000.0, 12000.0),
+            // Down through both caps at y = 4000 and 2000.
+            ((0.0, 1e4, 0.0), (0.0, -1.0, 0.0), 6000.0, 8000.0),
+        ];
+
+        for (origin, direction, t0, t1) in table {
+            let xs = cyl.intersect(&ray(origin, direction));
+
+            assert_eq!(xs.len(), 2, "ray from {:?}", origin);
+            assert!((xs[0].t - t0).abs() / t0 < tolerance(1e-9), "{}", xs[0].t);
+            assert!((xs[1].t - t1).abs() / t1 < tolerance(1e-9), "{}", xs[1].t);
+        }
+    }
+}
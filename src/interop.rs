@@ -0,0 +1,140 @@
+//! Conversions to and from `glam` and `nalgebra` types, behind the features
+//! of the same names.
+//!
+//! Both libraries store matrices column by column while `Matrix4x4` stores
+//! rows, so the conversions go through columns explicitly. `glam` pairs with
+//! `DMat4`/`DVec4`, or `Mat4`/`Vec4` under the `f32` feature.
+
+use crate::matrix::Matrix4x4;
+use crate::tuple::{Elem, Tuple4};
+
+#[cfg(all(feature = "glam", not(feature = "f32")))]
+use glam::{DMat4 as GlamMat4, DVec4 as GlamVec4};
+#[cfg(all(feature = "glam", feature = "f32"))]
+use glam::{Mat4 as GlamMat4, Vec4 as GlamVec4};
+
+#[cfg(feature = "glam")]
+impl From<GlamMat4> for Matrix4x4 {
+    fn from(m: GlamMat4) -> Self {
+        Matrix4x4::from_cols(m.to_cols_array_2d())
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Matrix4x4> for GlamMat4 {
+    fn from(m: Matrix4x4) -> Self {
+        let cols: [[Elem; 4]; 4] = std::array::from_fn(|i| m.col(i));
+        GlamMat4::from_cols_array_2d(&cols)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<GlamVec4> for Tuple4 {
+    fn from(v: GlamVec4) -> Self {
+        Tuple4::from(v.to_array())
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Tuple4> for GlamVec4 {
+    fn from(t: Tuple4) -> Self {
+        GlamVec4::from_array(t.into())
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Matrix4<Elem>> for Matrix4x4 {
+    fn from(m: nalgebra::Matrix4<Elem>) -> Self {
+        Matrix4x4::from_rows(std::array::from_fn(|y| std::array::from_fn(|x| m[(y, x)])))
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<Matrix4x4> for nalgebra::Matrix4<Elem> {
+    fn from(m: Matrix4x4) -> Self {
+        nalgebra::Matrix4::from_fn(|y, x| m.get(y, x))
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Vector4<Elem>> for Tuple4 {
+    fn from(v: nalgebra::Vector4<Elem>) -> Self {
+        Tuple4::new(v.x, v.y, v.z, v.w)
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<Tuple4> for nalgebra::Vector4<Elem> {
+    fn from(t: Tuple4) -> Self {
+        nalgebra::Vector4::new(t.x, t.y, t.z, t.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::consts::PI;
+
+    fn transform() -> Matrix4x4 {
+        Matrix4x4::translation(1.0, -2.0, 3.0)
+            .rotate_x(PI / 3.0)
+            .scale(2.0, 0.5, 4.0)
+            .shear(0.1, 0.0, 0.2, 0.0, 0.0, 0.3)
+    }
+
+    #[test]
+    #[cfg(feature = "glam")]
+    fn test_glam_round_trip() {
+        let m = transform();
+        let t = Tuple4::point(4.0, -5.0, 6.0);
+
+        assert_eq!(Matrix4x4::from(GlamMat4::from(m)), m);
+        assert_eq!(Tuple4::from(GlamVec4::from(t)), t);
+        let translation = GlamMat4::from(Matrix4x4::translation(1.0, -2.0, 3.0));
+        assert_eq!(translation.col(3).to_array(), [1.0, -2.0, 3.0, 1.0]);
+    }
+
+    #[test]
+    #[cfg(feature = "glam")]
+    fn test_glam_products_agree() {
+        let (a, b) = (
+            transform(),
+            Matrix4x4::rotation_z(0.7).translate(0.0, 2.0, 0.0),
+        );
+        let t = Tuple4::point(4.0, -5.0, 6.0);
+
+        let product = GlamMat4::from(a) * GlamMat4::from(b);
+        let applied = GlamMat4::from(a) * GlamVec4::from(t);
+
+        crate::assert_approx_eq!(Matrix4x4::from(product), a * b);
+        crate::assert_approx_eq!(Tuple4::from(applied), a * t);
+    }
+
+    #[test]
+    #[cfg(feature = "nalgebra")]
+    fn test_nalgebra_round_trip() {
+        let m = transform();
+        let t = Tuple4::vector(4.0, -5.0, 6.0);
+
+        assert_eq!(Matrix4x4::from(nalgebra::Matrix4::from(m)), m);
+        assert_eq!(Tuple4::from(nalgebra::Vector4::from(t)), t);
+        let translation = nalgebra::Matrix4::from(Matrix4x4::translation(1.0, -2.0, 3.0));
+        assert_eq!(translation[(0, 3)], 1.0);
+    }
+
+    #[test]
+    #[cfg(feature = "nalgebra")]
+    fn test_nalgebra_products_agree() {
+        let (a, b) = (
+            transform(),
+            Matrix4x4::rotation_z(0.7).translate(0.0, 2.0, 0.0),
+        );
+        let t = Tuple4::point(4.0, -5.0, 6.0);
+
+        let product = nalgebra::Matrix4::from(a) * nalgebra::Matrix4::from(b);
+        let applied = nalgebra::Matrix4::from(a) * nalgebra::Vector4::from(t);
+
+        crate::assert_approx_eq!(Matrix4x4::from(product), a * b);
+        crate::assert_approx_eq!(Tuple4::from(applied), a * t);
+    }
+}
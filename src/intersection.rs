@@ -0,0 +1,223 @@
+use std::ops::Index;
+
+use crate::shape::Shape;
+use crate::tuple::Elem;
+
+pub struct Intersection<'a> {
+    pub t: Elem,
+    pub object: &'a dyn Shape,
+}
+
+impl Intersection<'_> {
+    pub fn new(t: Elem, object: &dyn Shape) -> Intersection<'_> {
+        Intersection { t, object }
+    }
+}
+
+/// Intersections kept in ascending order of `t`. Ties keep the order they
+/// were added in.
+pub struct Intersections<'a> {
+    intersections: Vec<Intersection<'a>>,
+}
+
+impl<'a> Intersections<'a> {
+    pub fn new(mut intersections: Vec<Intersection<'a>>) -> Intersections<'a> {
+        intersections.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Intersections { intersections }
+    }
+
+    pub fn len(&self) -> usize {
+        self.intersections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intersections.is_empty()
+    }
+
+    /// The intersection with the lowest non-negative `t`.
+    pub fn hit(&self) -> Option<&Intersection<'a>> {
+        self.intersections.iter().find(|x| x.t >= 0.0)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Intersection<'a>> {
+        self.intersections.iter()
+    }
+
+    /// Merges in another sorted collection in linear time.
+    pub fn merge(&mut self, other: Intersections<'a>) {
+        let mut merged = Vec::with_capacity(self.len() + other.len());
+        let mut a = std::mem::take(&mut self.intersections)
+            .into_iter()
+            .peekable();
+        let mut b = other.intersections.into_iter().peekable();
+
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let next = if y.t.total_cmp(&x.t).is_lt() {
+                b.next()
+            } else {
+                a.next()
+            };
+            merged.extend(next);
+        }
+        merged.extend(a);
+        merged.extend(b);
+
+        self.intersections = merged;
+    }
+}
+
+impl<'a> Extend<Intersection<'a>> for Intersections<'a> {
+    fn extend<I: IntoIterator<Item = Intersection<'a>>>(&mut self, iter: I) {
+        self.merge(Intersections::new(iter.into_iter().collect()));
+    }
+}
+
+impl<'a> IntoIterator for Intersections<'a> {
+    type Item = Intersection<'a>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intersections.into_iter()
+    }
+}
+
+impl<'a> Index<usize> for Intersections<'a> {
+    type Output = Intersection<'a>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.intersections[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+    use crate::ray::Ray;
+    use crate::sphere::Sphere;
+    use crate::tuple::Tuple4;
+
+    fn ts(xs: &Intersections<'_>) -> Vec<Elem> {
+        xs.iter().map(|x| x.t).collect()
+    }
+
+    #[test]
+    fn test_an_intersection_encapsulates_t_and_object() {
+        let s = Sphere::new();
+
+        let i = Intersection::new(3.5, &s);
+
+        assert_eq!(i.t, 3.5);
+        assert!(ptr::addr_eq(i.object, &s));
+    }
+
+    #[test]
+    fn test_intersections_are_sorted_by_t() {
+        let s = Sphere::new();
+
+        let xs = Intersections::new(vec![
+            Intersection::new(5.0, &s),
+            Intersection::new(-3.0, &s),
+            Intersection::new(2.0, &s),
+        ]);
+
+        assert_eq!(ts(&xs), vec![-3.0, 2.0, 5.0]);
+        assert_eq!(xs[1].t, 2.0);
+    }
+
+    #[test]
+    fn test_the_hit_when_all_intersections_have_positive_t() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(1.0, &s);
+        let i2 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+
+        let i = xs.hit().unwrap();
+
+        assert_eq!(i.t, 1.0);
+    }
+
+    #[test]
+    fn test_the_hit_when_some_intersections_have_negative_t() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(-1.0, &s);
+        let i2 = Intersection::new(1.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+
+        let i = xs.hit().unwrap();
+
+        assert_eq!(i.t, 1.0);
+    }
+
+    #[test]
+    fn test_the_hit_when_all_intersections_have_negative_t() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(-2.0, &s);
+        let i2 = Intersection::new(-1.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+
+        let i = xs.hit();
+
+        assert!(i.is_none());
+    }
+
+    #[test]
+    fn test_the_hit_is_always_the_lowest_nonnegative_intersection() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(5.0, &s);
+        let i2 = Intersection::new(7.0, &s);
+        let i3 = Intersection::new(-3.0, &s);
+        let i4 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i1, i2, i3, i4]);
+
+        let i = xs.hit().unwrap();
+
+        assert_eq!(i.t, 2.0);
+    }
+
+    #[test]
+    fn test_equal_t_values_keep_their_order() {
+        let (s1, s2) = (Sphere::new(), Sphere::new());
+
+        let xs = Intersections::new(vec![
+            Intersection::new(4.0, &s1),
+            Intersection::new(1.0, &s2),
+            Intersection::new(1.0, &s1),
+        ]);
+
+        assert!(ptr::addr_eq(xs.hit().unwrap().object, &s2));
+        assert!(ptr::addr_eq(xs[1].object, &s1));
+    }
+
+    #[test]
+    fn test_merging_keeps_intersections_sorted() {
+        let (s1, s2) = (Sphere::new(), Sphere::new());
+        let mut xs = Intersections::new(vec![
+            Intersection::new(1.0, &s1),
+            Intersection::new(4.0, &s1),
+        ]);
+
+        xs.merge(Intersections::new(vec![
+            Intersection::new(-2.0, &s2),
+            Intersection::new(1.0, &s2),
+            Intersection::new(6.0, &s2),
+        ]));
+
+        assert_eq!(ts(&xs), vec![-2.0, 1.0, 1.0, 4.0, 6.0]);
+        assert!(ptr::addr_eq(xs[1].object, &s1));
+        assert!(ptr::addr_eq(xs[2].object, &s2));
+    }
+
+    #[test]
+    fn test_extending_with_unsorted_intersections() {
+        let s = Sphere::new();
+        let mut xs = Intersections::new(Vec::new());
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
+
+        xs.extend(s.intersect(&r));
+        xs.extend([Intersection::new(5.0, &s), Intersection::new(-1.0, &s)]);
+
+        assert_eq!(ts(&xs), vec![-1.0, 4.0, 5.0, 6.0]);
+    }
+}
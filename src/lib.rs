@@ -1,9 +1,33 @@
+pub mod accumulator;
+pub mod approx;
+pub mod bounds;
+pub mod camera_paths;
 pub mod canvas;
 pub mod color;
+pub mod cone;
+pub mod cube;
+pub mod cylinder;
+#[cfg(any(feature = "glam", feature = "nalgebra"))]
+mod interop;
+pub mod intersection;
 pub mod lights;
 pub mod materials;
 pub mod matrix;
+pub mod plane;
 pub mod ppm;
+pub mod quaternion;
 pub mod ray;
+pub mod roots;
+pub mod sampling;
+pub mod shape;
+#[cfg(test)]
+mod shape_invariants;
+#[cfg(all(feature = "simd", not(feature = "f32"), target_arch = "x86_64"))]
+mod simd;
 pub mod sphere;
+#[cfg(test)]
+mod test_rng;
+pub mod transform;
+pub mod transform_parser;
+pub mod triangle;
 pub mod tuple;
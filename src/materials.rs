@@ -9,6 +9,25 @@ pub struct Material {
     pub shininess: f64,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum EnergyIssue {
+    NegativeCoefficient(&'static str),
+    AmbientAboveOne(f64),
+    ReflectanceAboveOne(f64),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct EnergyReport {
+    pub reflectance: f64,
+    pub issues: Vec<EnergyIssue>,
+}
+
+impl EnergyReport {
+    pub fn is_conserving(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
 impl Material {
     pub fn new(color: Color, ambient: f64, diffuse: f64, specular: f64, shininess: f64) -> Self {
         Material {
@@ -20,6 +39,45 @@ impl Material {
         }
     }
 
+    /// Checks whether the material reflects more light than it receives.
+    ///
+    /// The Phong specular lobe `cos^n` integrates to `2 / (n + 2)` of a
+    /// diffuse lobe, so the reflectance of direct light is
+    /// `max(color) * diffuse + specular * 2 / (shininess + 2)` and must not
+    /// exceed 1. Ambient stands in for indirect light and is checked on its
+    /// own against 1. Negative coefficients are always flagged.
+    pub fn energy_report(&self) -> EnergyReport {
+        let mut issues = Vec::new();
+        let coefficients = [
+            ("ambient", self.ambient),
+            ("diffuse", self.diffuse),
+            ("specular", self.specular),
+            ("shininess", self.shininess),
+        ];
+        for (name, value) in coefficients {
+            if value < 0.0 {
+                issues.push(EnergyIssue::NegativeCoefficient(name));
+            }
+        }
+
+        let albedo = self.color.r.max(self.color.g).max(self.color.b);
+        let reflectance =
+            albedo * self.diffuse + self.specular * 2.0 / (self.shininess.max(0.0) + 2.0);
+        if self.ambient * albedo > 1.0 {
+            issues.push(EnergyIssue::AmbientAboveOne(self.ambient * albedo));
+        }
+        if reflectance > 1.0 {
+            issues.push(EnergyIssue::ReflectanceAboveOne(reflectance));
+        }
+
+        EnergyReport {
+            reflectance,
+            issues,
+        }
+    }
+
+    // Shading stays in `f64`; the casts only do work under the `f32` feature.
+    #[allow(clippy::unnecessary_cast)]
     pub fn lighting(
         &self,
         light: PointLight,
@@ -31,20 +89,20 @@ impl Material {
         let lightv = (*light.position() - point).normalize();
         let ambient = effective_color * self.ambient;
 
-        let light_dot_normal = lightv.dot(&normalv);
+        let light_dot_normal = lightv.dot(&normalv) as f64;
         let diffuse;
         let specular;
         if light_dot_normal < 0.0 {
-            diffuse = Color::new(0.0, 0.0, 0.0);
-            specular = Color::new(0.0, 0.0, 0.0);
+            diffuse = Color::BLACK;
+            specular = Color::BLACK;
         } else {
             diffuse = effective_color * self.diffuse * light_dot_normal;
 
             let reflectv = (-1.0 * lightv).reflect(normalv);
-            let reflect_dot_eye = reflectv.dot(&eyev);
+            let reflect_dot_eye = reflectv.dot(&eyev) as f64;
 
             if reflect_dot_eye <= 0.0 {
-                specular = Color::new(0.0, 0.0, 0.0);
+                specular = Color::BLACK;
             } else {
                 let factor = reflect_dot_eye.powf(self.shininess);
                 specular = *light.intensity() * self.specular * factor;
@@ -58,7 +116,7 @@ impl Material {
 impl Default for Material {
     fn default() -> Self {
         Material {
-            color: Color::new(1.0, 1.0, 1.0),
+            color: Color::WHITE,
             ambient: 0.1,
             diffuse: 0.9,
             specular: 0.9,
@@ -69,9 +127,13 @@ impl Default for Material {
 
 #[cfg(test)]
 mod tests {
-    use crate::{color::Color, lights::PointLight, tuple::Tuple4};
+    use crate::{
+        color::Color,
+        lights::PointLight,
+        tuple::{Elem, Tuple4},
+    };
 
-    use super::Material;
+    use super::{EnergyIssue, Material};
 
     const EPSILON: f64 = 1e-6;
 
@@ -90,6 +152,52 @@ mod tests {
         assert_eq!(m.shininess, 200.0);
     }
 
+    #[test]
+    fn test_default_material_conserves_energy() {
+        let report = Material::default().energy_report();
+
+        assert!(report.is_conserving());
+        assert!(equal(report.reflectance, 0.9 + 0.9 * 2.0 / 202.0));
+    }
+
+    #[test]
+    fn test_over_unity_material_is_flagged() {
+        let m = Material {
+            diffuse: 0.9,
+            specular: 1.0,
+            shininess: 1.0,
+            ..Default::default()
+        };
+
+        let report = m.energy_report();
+
+        assert!(!report.is_conserving());
+        assert!(matches!(
+            report.issues[..],
+            [EnergyIssue::ReflectanceAboveOne(r)] if equal(r, 0.9 + 2.0 / 3.0)
+        ));
+    }
+
+    #[test]
+    fn test_bright_ambient_and_negative_coefficients_are_flagged() {
+        let m = Material {
+            color: Color::new(2.0, 1.0, 1.0),
+            ambient: 0.6,
+            diffuse: -0.1,
+            ..Default::default()
+        };
+
+        let report = m.energy_report();
+
+        assert_eq!(
+            report.issues,
+            vec![
+                EnergyIssue::NegativeCoefficient("diffuse"),
+                EnergyIssue::AmbientAboveOne(1.2)
+            ]
+        );
+    }
+
     #[test]
     fn test_lighting_with_eye_between_the_light_and_the_surface() {
         let m = Material::default();
@@ -107,7 +215,7 @@ mod tests {
     fn test_lighting_with_eye_between_the_light_and_the_surface_eye_offset_45_deg() {
         let m = Material::default();
         let position = Tuple4::point(0.0, 0.0, 0.0);
-        let eyev = Tuple4::vector(0.0, 2.0_f64.sqrt(), -(2.0_f64.sqrt()) / 2.0);
+        let eyev = Tuple4::vector(0.0, Elem::sqrt(2.0), -(Elem::sqrt(2.0)) / 2.0);
         let normalv = Tuple4::vector(0.0, 0.0, -1.0);
         let light = PointLight::new(Tuple4::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
@@ -135,7 +243,7 @@ mod tests {
     fn test_lighting_with_eye_in_the_path_of_the_reflection_vector() {
         let m = Material::default();
         let position = Tuple4::point(0.0, 0.0, 0.0);
-        let eyev = Tuple4::vector(0.0, -(2.0_f64.sqrt() / 2.0), -(2.0_f64.sqrt()) / 2.0);
+        let eyev = Tuple4::vector(0.0, -(Elem::sqrt(2.0) / 2.0), -(Elem::sqrt(2.0)) / 2.0);
         let normalv = Tuple4::vector(0.0, 0.0, -1.0);
         let light = PointLight::new(Tuple4::point(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
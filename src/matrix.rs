@@ -1,88 +1,265 @@
-use std::ops::Mul;
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, MulAssign, Neg, Sub};
+
+use crate::approx::ApproxEq;
+use crate::quaternion::Quaternion;
+use crate::tuple::{Elem, Tuple4};
+
+fn check_index(size: usize, y: usize, x: usize) {
+    assert!(
+        y < size && x < size,
+        "Matrix index ({}, {}) out of bounds for {}x{} matrix",
+        y,
+        x,
+        size,
+        size
+    );
+}
 
-use crate::tuple::Tuple4;
+fn fmt_rows(f: &mut fmt::Formatter<'_>, name: &str, size: usize, data: &[Elem]) -> fmt::Result {
+    let mut s = f.debug_struct(name);
+    for (i, row) in data.chunks(size).enumerate() {
+        s.field(&format!("row{}", i), &row);
+    }
 
-fn to_index(size: usize, y: usize, x: usize) -> usize {
-    y * size + x
+    s.finish()
 }
 
-fn to_yx(size: usize, i: usize) -> (usize, usize) {
-    let y = i / size;
-    let x = i % size;
+/// Writes one row per line with right-aligned columns, honoring the
+/// formatter's precision. The alternate flag adds row and column indices.
+fn fmt_grid(f: &mut fmt::Formatter<'_>, size: usize, data: &[Elem]) -> fmt::Result {
+    let cells: Vec<String> = match f.precision() {
+        Some(p) => data.iter().map(|n| format!("{:.*}", p, n)).collect(),
+        None => data.iter().map(|n| n.to_string()).collect(),
+    };
+    let width = cells.iter().map(|c| c.len()).max().unwrap_or(0);
+
+    if f.alternate() {
+        write!(f, " ")?;
+        for x in 0..size {
+            write!(f, " {:>width$}", x, width = width)?;
+        }
+        writeln!(f)?;
+    }
 
-    (y, x)
+    for (y, row) in cells.chunks(size).enumerate() {
+        if y > 0 {
+            writeln!(f)?;
+        }
+        if f.alternate() {
+            write!(f, "{}", y)?;
+        }
+        for (x, cell) in row.iter().enumerate() {
+            if x > 0 || f.alternate() {
+                write!(f, " ")?;
+            }
+            write!(f, "{:>width$}", cell, width = width)?;
+        }
+    }
+
+    Ok(())
 }
 
-type Elem = f64;
+#[cfg_attr(
+    all(feature = "simd", not(feature = "f32"), target_arch = "x86_64"),
+    allow(dead_code)
+)]
+pub(crate) fn mul_matrix_scalar(a: &[[Elem; 4]; 4], b: &[[Elem; 4]; 4]) -> [[Elem; 4]; 4] {
+    let mut data = [[0.0; 4]; 4];
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Matrix2x2 {
-    data: [Elem; Matrix2x2::size()],
+    for (y, row) in data.iter_mut().enumerate() {
+        for (x, n) in row.iter_mut().enumerate() {
+            *n = (0..4).map(|k| a[y][k] * b[k][x]).sum();
+        }
+    }
+
+    data
 }
 
-impl Matrix2x2 {
-    const N: usize = 2;
+#[cfg_attr(
+    all(feature = "simd", not(feature = "f32"), target_arch = "x86_64"),
+    allow(dead_code)
+)]
+pub(crate) fn mul_tuple_scalar(m: &[[Elem; 4]; 4], t: [Elem; 4]) -> [Elem; 4] {
+    let mut data = [0.0; 4];
 
-    const fn size() -> usize {
-        Matrix2x2::N * Matrix2x2::N
+    for (n, row) in data.iter_mut().zip(m.iter()) {
+        *n = row[0] * t[0] + row[1] * t[1] + row[2] * t[2] + row[3] * t[3];
     }
 
-    #[allow(dead_code)]
-    fn new(data: [Elem; Matrix2x2::size()]) -> Matrix2x2 {
-        Matrix2x2 { data }
-    }
+    data
+}
 
-    #[allow(dead_code)]
-    fn get(&self, y: usize, x: usize) -> Elem {
-        let i = to_index(Matrix2x2::N, y, x);
-        self.data[i]
+/// Reduces the leading `N` columns of `rows` to reduced row echelon form
+/// with partial pivoting, applying the same row operations to any extra
+/// columns. Pivots no larger than `epsilon` count as zero. Returns the
+/// number of pivots and the determinant of the leading NxN block, which is
+/// zero whenever a pivot is missing.
+fn gauss_jordan<const N: usize, const C: usize>(
+    rows: &mut [[Elem; C]; N],
+    epsilon: Elem,
+) -> (usize, Elem) {
+    let mut pivot_row = 0;
+    let mut det = 1.0;
+
+    for col in 0..N {
+        if pivot_row == N {
+            break;
+        }
+
+        let best = (pivot_row..N)
+            .max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))
+            .unwrap();
+        if rows[best][col].abs() <= epsilon {
+            det = 0.0;
+            continue;
+        }
+        if best != pivot_row {
+            rows.swap(pivot_row, best);
+            det = -det;
+        }
+
+        let pivot = rows[pivot_row][col];
+        det *= pivot;
+        for v in rows[pivot_row].iter_mut() {
+            *v /= pivot;
+        }
+
+        let pivot_values = rows[pivot_row];
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != pivot_row && factor != 0.0 {
+                for (v, p) in row.iter_mut().zip(pivot_values.iter()) {
+                    *v -= factor * p;
+                }
+            }
+        }
+
+        pivot_row += 1;
     }
 
-    fn det(&self) -> Elem {
-        self.data[0] * self.data[3] - self.data[1] * self.data[2]
+    if pivot_row < N {
+        det = 0.0;
     }
+
+    (pivot_row, det)
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub struct Matrix3x3 {
-    data: [Elem; Matrix3x3::size()],
+/// A square `N`x`N` matrix stored row by row. With the `serde` feature it
+/// is serialized as `N` nested arrays of `N` numbers, one per row.
+#[derive(PartialEq, Clone, Copy)]
+pub struct Matrix<const N: usize> {
+    data: [[Elem; N]; N],
 }
 
-impl Matrix3x3 {
-    const N: usize = 3;
+pub type Matrix2x2 = Matrix<2>;
+pub type Matrix3x3 = Matrix<3>;
+pub type Matrix4x4 = Matrix<4>;
 
-    const fn size() -> usize {
-        Matrix3x3::N * Matrix3x3::N
+impl<const N: usize> Matrix<N> {
+    pub fn zero() -> Self {
+        Matrix {
+            data: [[0.0; N]; N],
+        }
     }
 
-    #[allow(dead_code)]
-    fn new(data: [Elem; Matrix3x3::size()]) -> Matrix3x3 {
-        Matrix3x3 { data }
+    pub fn from_rows(rows: [[Elem; N]; N]) -> Self {
+        Matrix { data: rows }
     }
 
-    #[allow(dead_code)]
-    fn get(&self, y: usize, x: usize) -> Elem {
-        let i = to_index(Matrix3x3::N, y, x);
+    pub fn from_cols(cols: [[Elem; N]; N]) -> Self {
+        Self::from_rows(cols).transpose()
+    }
+
+    pub fn get(&self, y: usize, x: usize) -> Elem {
+        self.data[y][x]
+    }
+
+    pub fn set(&mut self, y: usize, x: usize, value: Elem) {
+        self[(y, x)] = value;
+    }
+
+    pub fn row(&self, i: usize) -> [Elem; N] {
+        assert!(i < N, "Row {} out of bounds for {}x{} matrix", i, N, N);
         self.data[i]
     }
 
-    fn submatrix(&self, row: usize, col: usize) -> Matrix2x2 {
-        let mut data = [0.0; Matrix2x2::size()];
-        let mut j = 0;
+    pub fn col(&self, i: usize) -> [Elem; N] {
+        assert!(i < N, "Column {} out of bounds for {}x{} matrix", i, N, N);
+        self.data.map(|row| row[i])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = [Elem; N]> + '_ {
+        (0..N).map(|i| self.row(i))
+    }
 
-        for (i, n) in self.data.iter().enumerate() {
-            let (y, x) = self.get_yx(i);
-            if y != row && x != col {
-                data[j] = *n;
-                j += 1;
+    pub fn cols(&self) -> impl Iterator<Item = [Elem; N]> + '_ {
+        (0..N).map(|i| self.col(i))
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut matrix = *self;
+        for (y, row) in self.data.iter().enumerate() {
+            for (x, &n) in row.iter().enumerate() {
+                matrix.data[x][y] = n;
             }
         }
 
-        Matrix2x2 { data }
+        matrix
+    }
+
+    /// Sum of the diagonal.
+    pub fn trace(&self) -> Elem {
+        (0..N).map(|i| self.data[i][i]).sum()
+    }
+
+    /// The matrix with `row` and `col` removed. `M` must be `N - 1`.
+    fn submatrix<const M: usize>(&self, row: usize, col: usize) -> Matrix<M> {
+        assert!(
+            M + 1 == N,
+            "Submatrix of a {}x{} matrix must be {}x{}",
+            N,
+            N,
+            M,
+            M
+        );
+        let mut matrix = Matrix::<M>::zero();
+
+        for (y, out) in matrix.data.iter_mut().enumerate() {
+            let y = y + (y >= row) as usize;
+            for (x, n) in out.iter_mut().enumerate() {
+                let x = x + (x >= col) as usize;
+                *n = self.data[y][x];
+            }
+        }
+
+        matrix
+    }
+}
+
+impl Matrix2x2 {
+    #[allow(dead_code)]
+    fn new(data: [Elem; 4]) -> Self {
+        Matrix::from_rows([[data[0], data[1]], [data[2], data[3]]])
+    }
+
+    fn det(&self) -> Elem {
+        self.data[0][0] * self.data[1][1] - self.data[0][1] * self.data[1][0]
+    }
+}
+
+impl Matrix3x3 {
+    #[allow(dead_code)]
+    fn new(data: [Elem; 9]) -> Self {
+        let mut matrix = Matrix::zero();
+        matrix.data.as_flattened_mut().copy_from_slice(&data);
+
+        matrix
     }
 
     fn minor(&self, row: usize, col: usize) -> Elem {
-        self.submatrix(row, col).det()
+        self.submatrix::<2>(row, col).det()
     }
 
     fn cofactor(&self, row: usize, col: usize) -> Elem {
@@ -92,191 +269,530 @@ impl Matrix3x3 {
 
     fn det(&self) -> Elem {
         let mut det = 0.0;
-        for (i, &n) in self.data[..Self::N].iter().enumerate() {
+        for (i, &n) in self.data[0].iter().enumerate() {
             det += n * self.cofactor(0, i);
         }
 
         det
     }
-
-    fn get_yx(&self, i: usize) -> (usize, usize) {
-        to_yx(Matrix3x3::N, i)
-    }
-}
-
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub struct Matrix4x4 {
-    data: [Elem; Matrix4x4::size()],
 }
 
 impl Matrix4x4 {
-    const N: usize = 4;
-    const PRECISION: f64 = 1e-12;
+    #[cfg(not(feature = "f32"))]
+    const PRECISION: Elem = 1e-12;
+    #[cfg(not(feature = "f32"))]
+    const ROTATION_TOLERANCE: Elem = 1e-9;
+    #[cfg(not(feature = "f32"))]
+    const AFFINE_TOLERANCE: Elem = 1e-12;
+    #[cfg(feature = "f32")]
+    const PRECISION: Elem = 1e-6;
+    #[cfg(feature = "f32")]
+    const ROTATION_TOLERANCE: Elem = 1e-4;
+    #[cfg(feature = "f32")]
+    const AFFINE_TOLERANCE: Elem = 1e-6;
+
+    pub fn new(data: [Elem; 16]) -> Self {
+        let mut matrix = Matrix::zero();
+        matrix.data.as_flattened_mut().copy_from_slice(&data);
 
-    const fn size() -> usize {
-        Matrix4x4::N * Matrix4x4::N
-    }
-
-    pub fn new(data: [Elem; Matrix4x4::size()]) -> Self {
-        Matrix4x4 { data }
-    }
-
-    pub fn zero() -> Self {
-        Matrix4x4::new([0.0; Matrix4x4::size()])
+        matrix
     }
 
     pub fn identity() -> Self {
         let mut matrix = Matrix4x4::zero();
-        for i in 0..Matrix4x4::N {
-            matrix.data[i * (Matrix4x4::N + 1)] = 1.0;
+        for i in 0..4 {
+            matrix.data[i][i] = 1.0;
         }
 
         matrix
     }
 
+    /// Whether every element is within `DEFAULT_EPSILON` of `identity()`.
+    pub fn is_identity(&self) -> bool {
+        self.approx_eq_default(&Self::identity())
+    }
+
+    pub fn is_identity_with_epsilon(&self, epsilon: Elem) -> bool {
+        self.approx_eq(&Self::identity(), epsilon)
+    }
+
     pub fn translation(x: Elem, y: Elem, z: Elem) -> Self {
         let mut m = Self::identity();
-        m.data[3] = x;
-        m.data[7] = y;
-        m.data[11] = z;
+        m.data[0][3] = x;
+        m.data[1][3] = y;
+        m.data[2][3] = z;
 
         m
     }
 
     pub fn scaling(x: Elem, y: Elem, z: Elem) -> Self {
         let mut m = Self::identity();
-        m.data[0] = x;
-        m.data[5] = y;
-        m.data[10] = z;
+        m.data[0][0] = x;
+        m.data[1][1] = y;
+        m.data[2][2] = z;
 
         m
     }
 
     pub fn rotation_x(x: Elem) -> Self {
         let mut m = Self::identity();
-        m.data[5] = x.cos();
-        m.data[6] = -x.sin();
-        m.data[9] = x.sin();
-        m.data[10] = x.cos();
+        m.data[1][1] = x.cos();
+        m.data[1][2] = -x.sin();
+        m.data[2][1] = x.sin();
+        m.data[2][2] = x.cos();
 
         m
     }
 
     pub fn rotation_y(y: Elem) -> Self {
         let mut m = Self::identity();
-        m.data[0] = y.cos();
-        m.data[2] = y.sin();
-        m.data[8] = -y.sin();
-        m.data[10] = y.cos();
+        m.data[0][0] = y.cos();
+        m.data[0][2] = y.sin();
+        m.data[2][0] = -y.sin();
+        m.data[2][2] = y.cos();
 
         m
     }
 
     pub fn rotation_z(z: Elem) -> Self {
         let mut m = Self::identity();
-        m.data[0] = z.cos();
-        m.data[1] = -z.sin();
-        m.data[4] = z.sin();
-        m.data[5] = z.cos();
+        m.data[0][0] = z.cos();
+        m.data[0][1] = -z.sin();
+        m.data[1][0] = z.sin();
+        m.data[1][1] = z.cos();
 
         m
     }
 
+    /// Rotation by `angle` radians about `axis` through the origin, by
+    /// Rodrigues' formula. The axis doesn't need to be normalized; the unit
+    /// axes give exactly [`Matrix4x4::rotation_x`], `_y` and `_z`.
+    ///
+    /// Panics if `axis` is a point or the zero vector.
+    pub fn rotation_axis(axis: Tuple4, angle: Elem) -> Self {
+        assert!(axis.is_vector(), "Rotation axis must be a vector");
+        assert!(axis.magnitude() > 0.0, "Rotation axis can't be zero");
+
+        let Tuple4 { x, y, z, .. } = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+
+        // The diagonal is `cos + t * u²` rearranged so that a component of
+        // 0 or 1 yields exactly `cos` or 1.
+        Matrix4x4::new([
+            x * x + (1.0 - x * x) * cos,
+            x * y * t - z * sin,
+            x * z * t + y * sin,
+            0.0,
+            x * y * t + z * sin,
+            y * y + (1.0 - y * y) * cos,
+            y * z * t - x * sin,
+            0.0,
+            x * z * t - y * sin,
+            y * z * t + x * sin,
+            z * z + (1.0 - z * z) * cos,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+        ])
+    }
+
     pub fn shearing(xy: Elem, xz: Elem, yx: Elem, yz: Elem, zx: Elem, zy: Elem) -> Self {
         let mut m = Self::identity();
-        m.data[1] = xy;
-        m.data[2] = xz;
-        m.data[4] = yx;
-        m.data[6] = yz;
-        m.data[8] = zx;
-        m.data[9] = zy;
+        m.data[0][1] = xy;
+        m.data[0][2] = xz;
+        m.data[1][0] = yx;
+        m.data[1][2] = yz;
+        m.data[2][0] = zx;
+        m.data[2][1] = zy;
 
         m
     }
 
-    pub fn get(&self, y: usize, x: usize) -> Elem {
-        let i = self.get_index(y, x);
-        self.data[i]
+    /// Applies a translation after `self`, so chained calls read in the order
+    /// the transformations happen.
+    pub fn translate(self, x: Elem, y: Elem, z: Elem) -> Self {
+        Matrix4x4::translation(x, y, z) * self
     }
 
-    pub fn transpose(self) -> Self {
-        let mut data = self.data;
-        for y in 0..Matrix4x4::N {
-            for x in y..Matrix4x4::N {
-                let old_i = self.get_index(y, x);
-                let new_i = self.get_index(x, y);
-                data.swap(new_i, old_i);
-            }
-        }
+    pub fn scale(self, x: Elem, y: Elem, z: Elem) -> Self {
+        Matrix4x4::scaling(x, y, z) * self
+    }
+
+    pub fn rotate_x(self, x: Elem) -> Self {
+        Matrix4x4::rotation_x(x) * self
+    }
+
+    pub fn rotate_y(self, y: Elem) -> Self {
+        Matrix4x4::rotation_y(y) * self
+    }
 
-        Matrix4x4 { data }
+    pub fn rotate_z(self, z: Elem) -> Self {
+        Matrix4x4::rotation_z(z) * self
+    }
+
+    pub fn rotate_axis(self, axis: Tuple4, angle: Elem) -> Self {
+        Matrix4x4::rotation_axis(axis, angle) * self
+    }
+
+    pub fn shear(self, xy: Elem, xz: Elem, yx: Elem, yz: Elem, zx: Elem, zy: Elem) -> Self {
+        Matrix4x4::shearing(xy, xz, yx, yz, zx, zy) * self
+    }
+
+    pub fn view_transform(from: Tuple4, to: Tuple4, up: Tuple4) -> Self {
+        let forward = (to - from).normalize();
+        let left = forward.cross(up.normalize());
+        let true_up = left.cross(forward);
+        let orientation = Matrix4x4::new([
+            left.x, left.y, left.z, 0.0, true_up.x, true_up.y, true_up.z, 0.0, -forward.x,
+            -forward.y, -forward.z, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ]);
+
+        orientation * Matrix4x4::translation(-from.x, -from.y, -from.z)
     }
 
     pub fn det(&self) -> Elem {
-        let mut det = 0.0;
-        for (i, &n) in self.data[..Self::N].iter().enumerate() {
-            det += n * self.cofactor(0, i);
+        let mut rows = self.data;
+        gauss_jordan(&mut rows, 0.0).1
+    }
+
+    /// `self` multiplied by itself `n` times, by repeated squaring.
+    pub fn pow(&self, mut n: u32) -> Self {
+        let mut result = Matrix4x4::identity();
+        let mut base = *self;
+        while n > 0 {
+            if n & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            n >>= 1;
         }
 
-        det
+        result
     }
 
     pub fn is_invertible(&self) -> bool {
-        self.is_invertible_with_det().0
+        self.det().abs() >= Self::PRECISION
     }
 
-    pub fn inverse(self) -> Option<Self> {
-        let (is_invertible, det) = self.is_invertible_with_det();
-        if !is_invertible {
+    pub fn inverse(&self) -> Option<Self> {
+        self.try_inverse()
+    }
+
+    /// Inverts the matrix, or returns `None` if `|det|` is below
+    /// `PRECISION`.
+    ///
+    /// Runs Gauss–Jordan elimination with partial pivoting on the matrix
+    /// augmented with the identity, which yields the determinant and the
+    /// inverse in one pass.
+    pub fn try_inverse(&self) -> Option<Self> {
+        let mut rows = [[0.0; 8]; 4];
+        for (y, row) in rows.iter_mut().enumerate() {
+            row[..4].copy_from_slice(&self.data[y]);
+            row[4 + y] = 1.0;
+        }
+
+        let (_, det) = gauss_jordan(&mut rows, 0.0);
+        if det.abs() < Self::PRECISION {
             return None;
         }
+
         let mut matrix = Matrix4x4::zero();
-        for y in 0..Matrix4x4::N {
-            for x in 0..Matrix4x4::N {
-                let c = self.cofactor(y, x);
-                let i = self.get_index(x, y);
-                matrix.data[i] = c / det;
+        for (out, row) in matrix.data.iter_mut().zip(rows.iter()) {
+            out.copy_from_slice(&row[4..]);
+        }
+
+        Some(matrix)
+    }
+
+    /// Solves `self * x = b` for `x`, or returns `None` under the same
+    /// `PRECISION` criterion as `try_inverse`.
+    ///
+    /// Eliminates with partial pivoting on the matrix augmented with `b`
+    /// alone, which is cheaper and loses less precision than forming the
+    /// inverse for a single tuple.
+    pub fn solve(&self, b: Tuple4) -> Option<Tuple4> {
+        let b = [b.x, b.y, b.z, b.w];
+        let mut rows = [[0.0; 5]; 4];
+        for (y, row) in rows.iter_mut().enumerate() {
+            row[..4].copy_from_slice(&self.data[y]);
+            row[4] = b[y];
+        }
+
+        let (_, det) = gauss_jordan(&mut rows, 0.0);
+        if det.abs() < Self::PRECISION {
+            return None;
+        }
+
+        Some(Tuple4::new(rows[0][4], rows[1][4], rows[2][4], rows[3][4]))
+    }
+
+    /// Inverts a matrix whose bottom row is `[0, 0, 0, 1]` by inverting the
+    /// upper-left 3x3 and mapping the translation through it, which skips
+    /// most of the work of `try_inverse`. Any other matrix falls back to
+    /// `try_inverse`.
+    pub fn inverse_affine(&self) -> Option<Self> {
+        if !self.is_affine() {
+            return self.try_inverse();
+        }
+
+        let linear = self.submatrix::<3>(3, 3);
+        let det = linear.det();
+        if det.abs() < Self::PRECISION {
+            return None;
+        }
+
+        let mut matrix = Matrix4x4::identity();
+        for y in 0..3 {
+            for x in 0..3 {
+                matrix.data[x][y] = linear.cofactor(y, x) / det;
             }
         }
+        for y in 0..3 {
+            let t: Elem = (0..3).map(|x| matrix.data[y][x] * self.data[x][3]).sum();
+            matrix.data[y][3] = -t;
+        }
 
         Some(matrix)
     }
 
-    fn is_invertible_with_det(&self) -> (bool, Elem) {
-        let det = self.det();
-        (det.abs() >= Self::PRECISION, det)
+    /// Returns the reduced row echelon form and the number of pivots,
+    /// treating pivots below `PRECISION` as zero.
+    pub fn row_reduce(&self) -> (Self, usize) {
+        let mut rows = self.data;
+        let (pivots, _) = gauss_jordan(&mut rows, Self::PRECISION);
+
+        (Matrix4x4::from_rows(rows), pivots)
     }
 
-    fn submatrix(&self, row: usize, col: usize) -> Matrix3x3 {
-        let mut data = [0.0; Matrix3x3::size()];
-        let mut j = 0;
+    pub fn rank(&self, epsilon: Elem) -> usize {
+        let mut rows = self.data;
+        gauss_jordan(&mut rows, epsilon).0
+    }
+
+    /// Re-orthonormalizes the upper-left 3x3 with Gram–Schmidt on its
+    /// columns, keeping the translation column and the handedness.
+    pub fn orthonormalize(&self) -> Self {
+        let x = self.basis(0).normalize();
+        let y = self.basis(1);
+        let y = (y - x * x.dot(&y)).normalize();
+        let handedness = self.basis(0).cross(self.basis(1)).dot(&self.basis(2));
+        let mut z = x.cross(y);
+        if handedness < 0.0 {
+            z = z * -1.0;
+        }
+
+        let mut m = *self;
+        for (col, v) in [x, y, z].iter().enumerate() {
+            m.data[0][col] = v.x;
+            m.data[1][col] = v.y;
+            m.data[2][col] = v.z;
+        }
+
+        m
+    }
 
-        for (i, n) in self.data.iter().enumerate() {
-            let (y, x) = self.get_yx(i);
-            if y != row && x != col {
-                data[j] = *n;
-                j += 1;
+    /// Largest deviation of `RᵀR` from the identity, where `R` is the
+    /// upper-left 3x3.
+    pub fn rotation_error(&self) -> Elem {
+        let basis = [self.basis(0), self.basis(1), self.basis(2)];
+        let mut error: Elem = 0.0;
+        for (i, a) in basis.iter().enumerate() {
+            for (j, b) in basis.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                error = error.max((a.dot(b) - expected).abs());
             }
         }
 
-        Matrix3x3 { data }
+        error
+    }
+
+    /// Whether the upper-left 3x3 is a proper rotation (orthonormal with
+    /// a positive determinant); the translation column is ignored.
+    pub fn is_rotation(&self) -> bool {
+        let handedness = self.basis(0).cross(self.basis(1)).dot(&self.basis(2));
+        self.rotation_error() < Self::ROTATION_TOLERANCE && handedness > 0.0
+    }
+
+    /// Splits the matrix into a translation vector, a rotation and per-axis
+    /// scale factors such that `translation(t) * r * scaling(s)`
+    /// reproduces it.
+    ///
+    /// A reflection is folded into the x scale, so `r` is always a proper
+    /// rotation. Returns `None` if the matrix is not affine, collapses an
+    /// axis, or shears (its scaled axes are not orthogonal).
+    pub fn decompose(&self) -> Option<(Tuple4, Self, Tuple4)> {
+        if !self.is_affine() {
+            return None;
+        }
+
+        let translation = self.basis(3);
+        let mut axes = [self.basis(0), self.basis(1), self.basis(2)];
+        let mut scale = axes.map(|a| a.magnitude());
+        if scale.iter().any(|&s| s < Self::PRECISION) {
+            return None;
+        }
+        if axes[0].cross(axes[1]).dot(&axes[2]) < 0.0 {
+            scale[0] = -scale[0];
+        }
+
+        let mut rotation = Matrix4x4::identity();
+        for (col, (axis, s)) in axes.iter_mut().zip(scale).enumerate() {
+            *axis = *axis / s;
+            rotation.data[0][col] = axis.x;
+            rotation.data[1][col] = axis.y;
+            rotation.data[2][col] = axis.z;
+        }
+        if !rotation.is_rotation() {
+            return None;
+        }
+
+        Some((
+            translation,
+            rotation,
+            Tuple4::vector(scale[0], scale[1], scale[2]),
+        ))
+    }
+
+    /// Interpolates between two transforms by decomposing both, lerping
+    /// translation and scale, and slerping the rotation. If either end
+    /// doesn't decompose, falls back to an elementwise lerp.
+    pub fn lerp_transform(&self, other: &Matrix4x4, t: Elem) -> Self {
+        let (Some((ta, ra, sa)), Some((tb, rb, sb))) = (self.decompose(), other.decompose()) else {
+            return *self * (1.0 - t) + *other * t;
+        };
+
+        let lerp = |a: Tuple4, b: Tuple4| a + (b - a) * t;
+        let translation = lerp(ta, tb);
+        let scale = lerp(sa, sb);
+        let rotation = Quaternion::from_matrix(&ra)
+            .slerp(Quaternion::from_matrix(&rb), t)
+            .to_matrix();
+
+        Matrix4x4::translation(translation.x, translation.y, translation.z)
+            * rotation
+            * Matrix4x4::scaling(scale.x, scale.y, scale.z)
+    }
+
+    /// Whether the bottom row is `[0, 0, 0, 1]` within `AFFINE_TOLERANCE`.
+    fn is_affine(&self) -> bool {
+        let bottom = [0.0, 0.0, 0.0, 1.0];
+        self.data[3]
+            .iter()
+            .zip(bottom.iter())
+            .all(|(a, b)| (a - b).abs() <= Self::AFFINE_TOLERANCE)
     }
 
+    fn basis(&self, col: usize) -> Tuple4 {
+        Tuple4::vector(self.get(0, col), self.get(1, col), self.get(2, col))
+    }
+
+    #[allow(dead_code)]
     fn minor(&self, row: usize, col: usize) -> Elem {
-        self.submatrix(row, col).det()
+        self.submatrix::<3>(row, col).det()
     }
 
+    #[allow(dead_code)]
     fn cofactor(&self, row: usize, col: usize) -> Elem {
         let n = if (row + col) % 2 == 1 { -1.0 } else { 1.0 };
         n * self.minor(row, col)
     }
+}
+
+impl<const N: usize> fmt::Debug for Matrix<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("Matrix{}x{}", N, N);
+        fmt_rows(f, &name, N, self.data.as_flattened())
+    }
+}
+
+impl<const N: usize> fmt::Display for Matrix<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_grid(f, N, self.data.as_flattened())
+    }
+}
+
+impl<const N: usize> From<[[Elem; N]; N]> for Matrix<N> {
+    fn from(rows: [[Elem; N]; N]) -> Self {
+        Matrix::from_rows(rows)
+    }
+}
+
+impl<const N: usize> ApproxEq for Matrix<N> {
+    fn approx_elements(&self) -> Cow<'_, [Elem]> {
+        Cow::Borrowed(self.data.as_flattened())
+    }
+}
+
+impl<const N: usize> Index<(usize, usize)> for Matrix<N> {
+    type Output = Elem;
+
+    fn index(&self, (y, x): (usize, usize)) -> &Self::Output {
+        check_index(N, y, x);
+        &self.data[y][x]
+    }
+}
+
+impl<const N: usize> IndexMut<(usize, usize)> for Matrix<N> {
+    fn index_mut(&mut self, (y, x): (usize, usize)) -> &mut Self::Output {
+        check_index(N, y, x);
+        &mut self.data[y][x]
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Matrix<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.data.iter().map(|row| row.as_slice()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Matrix<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let expected = format!("{} rows of {} numbers", N, N);
+        let rows = Vec::<Vec<Elem>>::deserialize(deserializer)?;
+        if rows.len() != N {
+            return Err(D::Error::invalid_length(rows.len(), &expected.as_str()));
+        }
+
+        let mut matrix = Matrix::zero();
+        for (out, row) in matrix.data.iter_mut().zip(rows) {
+            if row.len() != N {
+                return Err(D::Error::invalid_length(row.len(), &expected.as_str()));
+            }
+            out.copy_from_slice(&row);
+        }
+
+        Ok(matrix)
+    }
+}
+
+impl Mul<&Matrix4x4> for &Matrix4x4 {
+    type Output = Matrix4x4;
+
+    fn mul(self, rhs: &Matrix4x4) -> Self::Output {
+        *self * *rhs
+    }
+}
 
-    fn get_index(&self, y: usize, x: usize) -> usize {
-        to_index(Matrix4x4::N, y, x)
+impl Mul<Matrix4x4> for &Matrix4x4 {
+    type Output = Matrix4x4;
+
+    fn mul(self, rhs: Matrix4x4) -> Self::Output {
+        *self * rhs
     }
+}
+
+impl Mul<&Matrix4x4> for Matrix4x4 {
+    type Output = Matrix4x4;
 
-    fn get_yx(&self, i: usize) -> (usize, usize) {
-        to_yx(Matrix4x4::N, i)
+    fn mul(self, rhs: &Matrix4x4) -> Self::Output {
+        self * *rhs
     }
 }
 
@@ -284,32 +800,106 @@ impl Mul<Matrix4x4> for Matrix4x4 {
     type Output = Self;
 
     fn mul(self, rhs: Matrix4x4) -> Self::Output {
-        let mut data = [0.0; Matrix4x4::size()];
-
-        for y in 0..Matrix4x4::N {
-            for x in 0..Matrix4x4::N {
-                let n: Elem = (0..Matrix4x4::N)
-                    .map(|n| self.get(y, n) * rhs.get(n, x))
-                    .sum();
-                let i = to_index(Matrix4x4::N, y, x);
-                data[i] = n;
-            }
+        #[cfg(all(feature = "simd", not(feature = "f32"), target_arch = "x86_64"))]
+        let data = crate::simd::mul_matrix(&self.data, &rhs.data);
+        #[cfg(not(all(feature = "simd", not(feature = "f32"), target_arch = "x86_64")))]
+        let data = mul_matrix_scalar(&self.data, &rhs.data);
+
+        Matrix { data }
+    }
+}
+
+impl<const N: usize> Mul<Elem> for Matrix<N> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: Elem) -> Self::Output {
+        for n in self.data.as_flattened_mut() {
+            *n *= rhs;
         }
 
-        Matrix4x4 { data }
+        self
     }
 }
 
-impl Mul<Tuple4> for Matrix4x4 {
+impl<const N: usize> Mul<Matrix<N>> for Elem {
+    type Output = Matrix<N>;
+
+    fn mul(self, rhs: Matrix<N>) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl<const N: usize> Add for Matrix<N> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        for (n, m) in self
+            .data
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(rhs.data.as_flattened())
+        {
+            *n += m;
+        }
+
+        self
+    }
+}
+
+impl<const N: usize> Sub for Matrix<N> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        for (n, m) in self
+            .data
+            .as_flattened_mut()
+            .iter_mut()
+            .zip(rhs.data.as_flattened())
+        {
+            *n -= m;
+        }
+
+        self
+    }
+}
+
+impl<const N: usize> Neg for Matrix<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self * -1.0
+    }
+}
+
+impl MulAssign<&Matrix4x4> for Matrix4x4 {
+    fn mul_assign(&mut self, rhs: &Matrix4x4) {
+        *self = *self * *rhs;
+    }
+}
+
+impl MulAssign<Matrix4x4> for Matrix4x4 {
+    fn mul_assign(&mut self, rhs: Matrix4x4) {
+        *self *= &rhs;
+    }
+}
+
+impl Mul<Tuple4> for &Matrix4x4 {
     type Output = Tuple4;
 
     fn mul(self, rhs: Tuple4) -> Self::Output {
-        let mut data = [0.0; Matrix4x4::N];
+        *self * rhs
+    }
+}
 
-        for (i, row) in self.data.chunks(Matrix4x4::N).enumerate() {
-            let n = row[0] * rhs.x + row[1] * rhs.y + row[2] * rhs.z + row[3] * rhs.w;
-            data[i] = n;
-        }
+impl Mul<Tuple4> for Matrix4x4 {
+    type Output = Tuple4;
+
+    fn mul(self, rhs: Tuple4) -> Self::Output {
+        let t = [rhs.x, rhs.y, rhs.z, rhs.w];
+        #[cfg(all(feature = "simd", not(feature = "f32"), target_arch = "x86_64"))]
+        let data = crate::simd::mul_tuple(&self.data, t);
+        #[cfg(not(all(feature = "simd", not(feature = "f32"), target_arch = "x86_64")))]
+        let data = mul_tuple_scalar(&self.data, t);
 
         Tuple4::new(data[0], data[1], data[2], data[3])
     }
@@ -317,18 +907,34 @@ impl Mul<Tuple4> for Matrix4x4 {
 
 #[cfg(test)]
 mod tests {
-    use std::f64::consts::PI;
+    use crate::tuple::consts::PI;
 
     use super::*;
+    use crate::test_rng::Lcg;
+    use crate::{approx::tolerance, assert_approx_eq};
 
-    const EPSILON: f64 = 1e-6;
+    const EPSILON: Elem = 1e-6;
 
-    fn equal(a: f64, b: f64) -> bool {
-        (a - b).abs() < EPSILON
+    // The cofactor expansion `det` and `inverse` used before switching to
+    // Gauss–Jordan elimination, kept as a reference.
+    fn cofactor_det(m: &Matrix4x4) -> Elem {
+        (0..4).map(|x| m.get(0, x) * m.cofactor(0, x)).sum()
+    }
+
+    fn cofactor_inverse(m: &Matrix4x4) -> Matrix4x4 {
+        let det = cofactor_det(m);
+        let mut inverse = Matrix4x4::zero();
+        for y in 0..4 {
+            for x in 0..4 {
+                inverse[(x, y)] = m.cofactor(y, x) / det;
+            }
+        }
+
+        inverse
     }
 
-    fn tuples_equal(a: &Tuple4, b: &Tuple4) -> bool {
-        equal(a.x, b.x) && equal(a.y, b.y) && equal(a.z, b.z) && a.w == b.w
+    fn equal(a: Elem, b: Elem) -> bool {
+        (a - b).abs() < EPSILON
     }
 
     #[test]
@@ -412,6 +1018,225 @@ mod tests {
         assert_eq!(matrix.get(3, 2), 15.5);
     }
 
+    #[test]
+    fn test_indexing_4x4_matrix() {
+        let matrix = Matrix4x4::new([
+            1.0, 2.0, 3.0, 4.0, 5.5, 6.5, 7.5, 8.5, 9.0, 10.0, 11.0, 12.0, 13.5, 14.5, 15.5, 16.5,
+        ]);
+
+        assert_eq!(matrix[(0, 3)], 4.0);
+        assert_eq!(matrix[(1, 0)], 5.5);
+        assert_eq!(matrix[(3, 2)], 15.5);
+    }
+
+    #[test]
+    fn test_writing_4x4_matrix_entries() {
+        let mut matrix = Matrix4x4::zero();
+
+        matrix[(2, 3)] = -1.0;
+        matrix.set(3, 2, 7.0);
+
+        assert_eq!(matrix.get(2, 3), -1.0);
+        assert_eq!(matrix[(3, 2)], 7.0);
+        assert_eq!(matrix[(3, 3)], 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix index (0, 4) out of bounds for 4x4 matrix")]
+    fn test_indexing_4x4_matrix_out_of_bounds() {
+        let _ = Matrix4x4::identity()[(0, 4)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix index (4, 1) out of bounds for 4x4 matrix")]
+    fn test_setting_4x4_matrix_out_of_bounds() {
+        Matrix4x4::identity().set(4, 1, 1.0);
+    }
+
+    #[test]
+    fn test_indexing_3x3_matrix() {
+        let mut matrix = Matrix3x3::new([-3.0, 5.0, 0.0, 1.0, -2.0, -7.0, 0.0, 1.0, 1.0]);
+
+        matrix[(1, 2)] = 4.0;
+
+        assert_eq!(matrix[(0, 1)], 5.0);
+        assert_eq!(matrix[(1, 2)], 4.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix index (3, 0) out of bounds for 3x3 matrix")]
+    fn test_indexing_3x3_matrix_out_of_bounds() {
+        let _ = Matrix3x3::new([0.0; 9])[(3, 0)];
+    }
+
+    #[test]
+    fn test_rows_round_trip() {
+        let rows = [
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ];
+
+        let matrix = Matrix4x4::from_rows(rows);
+
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(&matrix.row(i), row);
+        }
+        assert_eq!(matrix.rows().collect::<Vec<_>>(), rows.to_vec());
+        assert_eq!(Matrix4x4::from(rows), matrix);
+        assert_eq!(matrix.get(1, 2), 7.0);
+    }
+
+    #[test]
+    fn test_from_cols_is_the_transpose_of_from_rows() {
+        let x = [
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ];
+
+        let matrix = Matrix4x4::from_cols(x);
+
+        assert_eq!(matrix.transpose(), Matrix4x4::from_rows(x));
+        assert_eq!(matrix.col(2), x[2]);
+        assert_eq!(matrix.cols().collect::<Vec<_>>(), x.to_vec());
+    }
+
+    #[test]
+    fn test_building_a_transform_from_basis_columns() {
+        let matrix = Matrix4x4::from_cols([
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 3.0, 0.0, 0.0],
+            [0.0, 0.0, 4.0, 0.0],
+            [1.0, 2.0, 3.0, 1.0],
+        ]);
+
+        assert_eq!(
+            matrix,
+            Matrix4x4::translation(1.0, 2.0, 3.0) * Matrix4x4::scaling(2.0, 3.0, 4.0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Column 4 out of bounds for 4x4 matrix")]
+    fn test_column_out_of_bounds() {
+        Matrix4x4::identity().col(4);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_round_trip_is_exact() {
+        let m = Matrix4x4::rotation_x(0.1).translate(1.0 / 3.0, -PI, 1e-30);
+        let n = m.submatrix::<3>(3, 3);
+
+        let json = serde_json::to_string(&m).unwrap();
+        let back: Matrix4x4 = serde_json::from_str(&json).unwrap();
+        let back_3x3: Matrix3x3 =
+            serde_json::from_str(&serde_json::to_string(&n).unwrap()).unwrap();
+
+        assert!(json.starts_with("[["));
+        assert_eq!(
+            back.data.map(|row| row.map(Elem::to_bits)),
+            m.data.map(|row| row.map(Elem::to_bits))
+        );
+        assert_eq!(
+            back_3x3.data.map(|row| row.map(Elem::to_bits)),
+            n.data.map(|row| row.map(Elem::to_bits))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_rejects_wrong_shape() {
+        let short = serde_json::from_str::<Matrix4x4>("[[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0]]");
+        let ragged = serde_json::from_str::<Matrix3x3>("[[1, 0, 0], [0, 1], [0, 0, 1]]");
+
+        let short = short.unwrap_err().to_string();
+        let ragged = ragged.unwrap_err().to_string();
+        assert!(
+            short.contains("invalid length 3, expected 4 rows of 4 numbers"),
+            "{}",
+            short
+        );
+        assert!(
+            ragged.contains("invalid length 2, expected 3 rows of 3 numbers"),
+            "{}",
+            ragged
+        );
+    }
+
+    #[test]
+    fn test_debug_output_of_4x4_matrix_labels_rows() {
+        let matrix = Matrix4x4::translation(1.0, 2.0, 3.0);
+
+        let s = format!("{:?}", matrix);
+
+        assert_eq!(
+            s,
+            "Matrix4x4 { row0: [1.0, 0.0, 0.0, 1.0], row1: [0.0, 1.0, 0.0, 2.0], \
+             row2: [0.0, 0.0, 1.0, 3.0], row3: [0.0, 0.0, 0.0, 1.0] }"
+        );
+    }
+
+    #[test]
+    fn test_debug_output_of_smaller_matrices_labels_rows() {
+        let m2 = Matrix2x2::new([1.0, 2.0, 3.0, 4.0]);
+        let m3 = Matrix3x3::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+
+        assert_eq!(
+            format!("{:?}", m2),
+            "Matrix2x2 { row0: [1.0, 2.0], row1: [3.0, 4.0] }"
+        );
+        assert_eq!(
+            format!("{:?}", m3),
+            "Matrix3x3 { row0: [1.0, 2.0, 3.0], row1: [4.0, 5.0, 6.0], row2: [7.0, 8.0, 9.0] }"
+        );
+    }
+
+    #[test]
+    fn test_display_of_4x4_matrix_aligns_columns() {
+        let matrix = Matrix4x4::translation(-12.5, 3.0, 0.25);
+
+        assert_eq!(
+            format!("{:.2}", matrix),
+            [
+                "  1.00   0.00   0.00 -12.50",
+                "  0.00   1.00   0.00   3.00",
+                "  0.00   0.00   1.00   0.25",
+                "  0.00   0.00   0.00   1.00",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_alternate_display_of_3x3_matrix_adds_indices() {
+        let matrix = Matrix3x3::new([1.0, -2.0, 3.0, 4.0, 5.5, -6.0, 7.0, 8.0, 9.0]);
+
+        assert_eq!(
+            format!("{:#.1}", matrix),
+            [
+                "     0    1    2",
+                "0  1.0 -2.0  3.0",
+                "1  4.0  5.5 -6.0",
+                "2  7.0  8.0  9.0",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_display_without_precision_uses_shortest_form() {
+        let matrix = Matrix3x3::new([1.0, 0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+
+        assert_eq!(
+            format!("{}", matrix),
+            "  1 0.5   0\n  0   1   0\n  0   0   1"
+        );
+    }
+
     #[test]
     fn test_multiplying_two_matrices() {
         let a = Matrix4x4::new([
@@ -421,49 +1246,196 @@ mod tests {
             -2.0, 1.0, 2.0, 3.0, 3.0, 2.0, 1.0, -1.0, 4.0, 3.0, 6.0, 5.0, 1.0, 2.0, 7.0, 8.0,
         ]);
 
-        let result = a * b;
+        let result = a * b;
+
+        assert_eq!(
+            result,
+            Matrix4x4::new([
+                20.0, 22.0, 50.0, 48.0, 44.0, 54.0, 114.0, 108.0, 40.0, 58.0, 110.0, 102.0, 16.0,
+                26.0, 46.0, 42.0
+            ])
+        );
+    }
+
+    #[test]
+    fn test_multiplying_matrix_with_tuple() {
+        let matrix = Matrix4x4::new([
+            1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+        ]);
+        let tuple = Tuple4::point(1.0, 2.0, 3.0);
+
+        let result = matrix * tuple;
+
+        assert_eq!(result, Tuple4::point(18.0, 24.0, 33.0));
+    }
+
+    #[test]
+    fn test_multiplying_matrix_by_identity_matrix() {
+        let matrix = Matrix4x4::new([
+            0.0, 1.0, 2.0, 4.0, 1.0, 2.0, 4.0, 8.0, 2.0, 4.0, 8.0, 16.0, 4.0, 8.0, 16.0, 32.0,
+        ]);
+        let identity = Matrix4x4::identity();
+
+        let result = matrix * identity;
+
+        assert_eq!(result, matrix);
+    }
+
+    #[test]
+    fn test_multiplying_identity_matrix_by_tuple() {
+        let identity = Matrix4x4::identity();
+        let tuple = Tuple4::new(1.0, 2.0, 3.0, 4.0);
+
+        let result = identity * tuple;
+
+        assert_eq!(result, tuple);
+    }
+
+    #[test]
+    fn test_multiplying_borrowed_matrices() {
+        let a = Matrix4x4::new([
+            1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0,
+        ]);
+        let b = Matrix4x4::new([
+            -2.0, 1.0, 2.0, 3.0, 3.0, 2.0, 1.0, -1.0, 4.0, 3.0, 6.0, 5.0, 1.0, 2.0, 7.0, 8.0,
+        ]);
+        let expected = a * b;
+        let (borrowed_a, borrowed_b) = (&a, &b);
+
+        assert_eq!(borrowed_a * borrowed_b, expected);
+        assert_eq!(borrowed_a * b, expected);
+        assert_eq!(a * borrowed_b, expected);
+    }
+
+    #[test]
+    fn test_multiplying_borrowed_matrix_with_tuple() {
+        let transform = Matrix4x4::translation(1.0, 2.0, 3.0);
+        let p = Tuple4::point(1.0, 1.0, 1.0);
+        let borrowed = &transform;
+
+        let result = borrowed * p;
+
+        assert_eq!(result, Tuple4::point(2.0, 3.0, 4.0));
+        assert_eq!(result, transform * p);
+    }
+
+    #[test]
+    fn test_accumulating_transforms_in_place() {
+        let step = Matrix4x4::rotation_y(0.1) * Matrix4x4::translation(0.5, 0.0, -0.25);
+        let mut accumulated = Matrix4x4::identity();
+
+        for i in 0..10 {
+            if i % 2 == 0 {
+                accumulated *= step;
+            } else {
+                accumulated *= &step;
+            }
+        }
+
+        let expected = (0..10).fold(Matrix4x4::identity(), |m, _| m * step);
+        assert_eq!(accumulated, expected);
+    }
+
+    #[test]
+    fn test_multiply_assigning_identity_is_a_no_op() {
+        let original = Matrix4x4::shearing(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        let mut matrix = original;
+
+        matrix *= Matrix4x4::identity();
+
+        assert_eq!(matrix, original);
+    }
+
+    #[test]
+    fn test_scaling_a_matrix_equals_adding_it_to_itself() {
+        let m = Matrix4x4::new([
+            1.0, 2.0, 3.0, 4.0, 5.5, 6.5, 7.5, 8.5, 9.0, 10.0, 11.0, 12.0, 13.5, 14.5, 15.5, 16.5,
+        ]);
+
+        assert_eq!(2.0 * m, m + m);
+        assert_eq!(m * 2.0, m + m);
+    }
+
+    #[test]
+    fn test_subtracting_a_matrix_from_itself_gives_zero() {
+        let m = Matrix4x4::rotation_x(0.3) * Matrix4x4::translation(1.0, -2.0, 3.0);
+
+        assert_eq!(m - m, Matrix4x4::zero());
+        assert_eq!(m + -m, Matrix4x4::zero());
+    }
+
+    #[test]
+    fn test_matrix_times_its_inverse_minus_identity_is_near_zero() {
+        let m = Matrix4x4::new([
+            8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0, -4.0,
+        ]);
+
+        let residual = m * m.inverse().unwrap() - Matrix4x4::identity();
+
+        assert_approx_eq!(residual, Matrix4x4::zero());
+    }
+
+    #[test]
+    fn test_trace_of_identity() {
+        assert_eq!(Matrix4x4::identity().trace(), 4.0);
+    }
+
+    #[test]
+    fn test_trace_sums_the_diagonal() {
+        let m = Matrix4x4::new([
+            8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0, -4.0,
+        ]);
+
+        assert_eq!(m.trace(), 18.0);
+    }
+
+    #[test]
+    fn test_rotation_times_its_inverse_is_identity_within_epsilon() {
+        let m = Matrix4x4::rotation_x(0.3).rotate_y(1.1).rotate_z(-0.7);
 
-        assert_eq!(
-            result,
-            Matrix4x4::new([
-                20.0, 22.0, 50.0, 48.0, 44.0, 54.0, 114.0, 108.0, 40.0, 58.0, 110.0, 102.0, 16.0,
-                26.0, 46.0, 42.0
-            ])
-        );
+        let product = m * m.inverse().unwrap();
+
+        assert!(product.is_identity_with_epsilon(tolerance(1e-9)));
+        assert!(product.is_identity());
+        assert!(!product.is_identity_with_epsilon(0.0));
+        assert_ne!(product, Matrix4x4::identity());
     }
 
     #[test]
-    fn test_multiplying_matrix_with_tuple() {
-        let matrix = Matrix4x4::new([
-            1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0,
-        ]);
-        let tuple = Tuple4::new(1.0, 2.0, 3.0, 1.0);
+    fn test_translation_is_not_identity() {
+        assert!(Matrix4x4::identity().is_identity_with_epsilon(0.0));
+        assert!(!Matrix4x4::translation(0.0, 1e-3, 0.0).is_identity());
+    }
 
-        let result = matrix * tuple;
+    #[test]
+    fn test_power_of_zero_and_one() {
+        let m = Matrix4x4::rotation_x(0.3).translate(1.0, 2.0, 3.0);
 
-        assert_eq!(result, Tuple4::new(18.0, 24.0, 33.0, 1.0));
+        assert_eq!(m.pow(0), Matrix4x4::identity());
+        assert_eq!(m.pow(1), m);
     }
 
     #[test]
-    fn test_multiplying_matrix_by_identity_matrix() {
-        let matrix = Matrix4x4::new([
-            0.0, 1.0, 2.0, 4.0, 1.0, 2.0, 4.0, 8.0, 2.0, 4.0, 8.0, 16.0, 4.0, 8.0, 16.0, 32.0,
-        ]);
-        let identity = Matrix4x4::identity();
+    fn test_power_of_rotation_adds_angles() {
+        let m = Matrix4x4::rotation_z(PI / 8.0);
 
-        let result = matrix * identity;
+        assert_approx_eq!(m.pow(8), Matrix4x4::rotation_z(PI));
+    }
 
-        assert_eq!(result, matrix);
+    #[test]
+    fn test_power_of_translation_scales_offset() {
+        let m = Matrix4x4::translation(1.0, -2.0, 0.5);
+
+        assert_eq!(m.pow(5), Matrix4x4::translation(5.0, -10.0, 2.5));
     }
 
     #[test]
-    fn test_multiplying_identity_matrix_by_tuple() {
-        let identity = Matrix4x4::identity();
-        let tuple = Tuple4::new(1.0, 2.0, 3.0, 4.0);
+    fn test_power_matches_repeated_multiplication() {
+        let m = Matrix4x4::rotation_y(0.2).scale(1.1, 0.9, 1.0);
 
-        let result = identity * tuple;
+        let repeated = (0..13).fold(Matrix4x4::identity(), |acc, _| acc * m);
 
-        assert_eq!(result, tuple);
+        assert_approx_eq!(m.pow(13), repeated);
     }
 
     #[test]
@@ -515,7 +1487,7 @@ mod tests {
 
         let det = matrix.det();
 
-        assert_eq!(det, -4071.0);
+        assert!((det - -4071.0).abs() < 1e-9);
     }
 
     #[test]
@@ -526,7 +1498,7 @@ mod tests {
 
         let is_invertible = matrix.is_invertible();
 
-        assert_eq!(is_invertible, true);
+        assert!(is_invertible);
     }
 
     #[test]
@@ -537,7 +1509,7 @@ mod tests {
 
         let is_invertible = matrix.is_invertible();
 
-        assert_eq!(is_invertible, false);
+        assert!(!is_invertible);
     }
 
     #[test]
@@ -552,13 +1524,7 @@ mod tests {
             0.21805, 0.45113, 0.24060, -0.04511, -0.80827, -1.45677, -0.44361, 0.52068, -0.07895,
             -0.22368, -0.05263, 0.19737, -0.52256, -0.81391, -0.30075, 0.30639,
         ]);
-        for y in 0..4 {
-            for x in 0..4 {
-                let a = expected.get(y, x);
-                let b = inverse.get(y, x);
-                assert!((a - b).abs() < 1e-5)
-            }
-        }
+        assert_approx_eq!(inverse, expected, 1e-5);
     }
 
     #[test]
@@ -569,24 +1535,179 @@ mod tests {
 
         let double_inversed = matrix.inverse().unwrap().inverse().unwrap();
 
+        assert_approx_eq!(double_inversed, matrix, tolerance(Matrix4x4::PRECISION));
+    }
+
+    #[test]
+    fn test_inverse_of_non_invertible_matrix() {
+        let matrix = Matrix4x4::new([
+            -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0,
+        ]);
+
+        let inverse = matrix.inverse();
+
+        assert_eq!(inverse, None);
+    }
+
+    #[test]
+    fn test_solving_matches_multiplying_by_inverse() {
+        let mut rng = Lcg::new(3);
+
+        for _ in 0..200 {
+            let m = Matrix4x4::new([0.0; 16].map(|_| rng.range(-10.0, 10.0)));
+            if m.det().abs() < 1.0 {
+                continue;
+            }
+            let b = Tuple4::new(
+                rng.range(-10.0, 10.0),
+                rng.range(-10.0, 10.0),
+                rng.range(-10.0, 10.0),
+                rng.range(-10.0, 10.0),
+            );
+
+            let x = m.solve(b).unwrap();
+
+            assert_approx_eq!(x, m.inverse().unwrap() * b, tolerance(1e-10));
+        }
+    }
+
+    #[test]
+    fn test_solving_a_transformed_point() {
+        let m = Matrix4x4::translation(1.0, 2.0, 3.0).scale(2.0, 2.0, 2.0);
+        let p = Tuple4::point(-3.0, 4.0, 0.5);
+
+        assert_approx_eq!(m.solve(m * p).unwrap(), p);
+    }
+
+    #[test]
+    fn test_solving_with_non_invertible_matrix() {
+        let matrix = Matrix4x4::new([
+            -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0,
+        ]);
+
+        assert_eq!(matrix.solve(Tuple4::point(1.0, 2.0, 3.0)), None);
+    }
+
+    #[test]
+    fn test_elimination_matches_cofactor_expansion() {
+        let matrices = [
+            Matrix4x4::new([
+                -5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0,
+                4.0,
+            ]),
+            Matrix4x4::new([
+                8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0, -4.0,
+            ]),
+            Matrix4x4::new([
+                9.0, 3.0, 0.0, 9.0, -5.0, -2.0, -6.0, -3.0, -4.0, 9.0, 6.0, 4.0, -7.0, 6.0, 6.0,
+                2.0,
+            ]),
+            Matrix4x4::new([
+                -2.0, -8.0, 3.0, 5.0, -3.0, 1.0, 7.0, 3.0, 1.0, 2.0, -9.0, 6.0, -6.0, 7.0, 7.0,
+                -9.0,
+            ]),
+        ];
+
+        for m in matrices {
+            let det = cofactor_det(&m);
+            assert!((m.det() - det).abs() < tolerance(1e-12) * det.abs());
+            assert_approx_eq!(m.inverse().unwrap(), cofactor_inverse(&m), tolerance(1e-9));
+        }
+    }
+
+    // Entries spanning twelve orders of magnitude are beyond `f32`.
+    #[test]
+    #[cfg(not(feature = "f32"))]
+    fn test_inverting_a_badly_scaled_matrix() {
+        let matrix = Matrix4x4::new([
+            1e-6, 1e6, 1.0, 0.0, 1.0, 1.0, 1e-6, 1e3, 1e6, 1e-3, 1.0, 1.0, 1.0, 1e-6, 1e6, 1.0,
+        ]);
+
+        let inverse = matrix.inverse().unwrap();
+        let reference = cofactor_inverse(&matrix);
+
+        assert_approx_eq!(matrix * inverse, Matrix4x4::identity(), 1e-9);
         for y in 0..4 {
             for x in 0..4 {
-                let a = matrix.get(y, x);
-                let b = double_inversed.get(y, x);
-                assert!((a - b).abs() < Matrix4x4::PRECISION)
+                let (a, b) = (inverse.get(y, x), reference.get(y, x));
+                assert!((a - b).abs() <= 1e-9 * b.abs().max(1e-9), "({}, {})", y, x);
             }
         }
+        assert!((matrix.det() - cofactor_det(&matrix)).abs() <= 1e-9 * matrix.det().abs());
     }
 
     #[test]
-    fn test_inverse_of_non_invertible_matrix() {
+    fn test_affine_inverse_matches_general_inverse() {
+        let chains = [
+            Matrix4x4::translation(5.0, -3.0, 2.0),
+            Matrix4x4::translation(10.0, 5.0, 7.0)
+                * Matrix4x4::rotation_x(PI / 2.0)
+                * Matrix4x4::scaling(5.0, 5.0, 5.0),
+            Matrix4x4::translation(-1.5, 0.25, 8.0)
+                * Matrix4x4::rotation_y(0.7)
+                * Matrix4x4::rotation_z(-1.3)
+                * Matrix4x4::scaling(0.5, 2.0, 3.0),
+            Matrix4x4::translation(0.0, 1.0, -4.0)
+                * Matrix4x4::shearing(1.0, 0.0, 0.5, 0.0, 0.0, 0.25)
+                * Matrix4x4::scaling(-1.0, 1.0, 2.0),
+        ];
+
+        for m in chains {
+            assert_approx_eq!(
+                m.inverse_affine().unwrap(),
+                m.inverse().unwrap(),
+                tolerance(1e-12)
+            );
+        }
+    }
+
+    #[test]
+    fn test_affine_inverse_of_singular_matrix() {
+        let m = Matrix4x4::translation(1.0, 2.0, 3.0) * Matrix4x4::scaling(1.0, 0.0, 1.0);
+
+        assert_eq!(m.inverse_affine(), None);
+    }
+
+    #[test]
+    fn test_affine_inverse_falls_back_for_projective_matrix() {
+        let mut m = Matrix4x4::translation(1.0, 2.0, 3.0) * Matrix4x4::rotation_x(0.4);
+        m[(3, 2)] = 0.5;
+
+        let inverse = m.inverse_affine().unwrap();
+
+        assert_eq!(Some(inverse), m.try_inverse());
+        assert_approx_eq!(m * inverse, Matrix4x4::identity());
+    }
+
+    #[test]
+    fn test_try_inverse_matches_inverse() {
+        let matrix = Matrix4x4::new([
+            8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0, -4.0,
+        ]);
+
+        let inverse = matrix.try_inverse().unwrap();
+
+        assert_eq!(Some(inverse), matrix.inverse());
+        assert_approx_eq!(matrix * inverse, Matrix4x4::identity());
+    }
+
+    #[test]
+    fn test_try_inverse_of_non_invertible_matrix() {
         let matrix = Matrix4x4::new([
             -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0,
         ]);
 
-        let inverse = matrix.inverse();
+        assert_eq!(matrix.try_inverse(), None);
+    }
 
-        assert_eq!(inverse, None);
+    #[test]
+    fn test_tiny_but_nonzero_determinant_is_treated_as_singular() {
+        let matrix = Matrix4x4::scaling(1e-13, 1.0, 1.0);
+
+        assert!(matrix.det() != 0.0);
+        assert!(!matrix.is_invertible());
+        assert_eq!(matrix.try_inverse(), None);
+        assert_eq!(matrix.inverse(), None);
     }
 
     #[test]
@@ -610,11 +1731,11 @@ mod tests {
     }
 
     #[test]
-    fn test_multiplying_vector_by_inverse_matrix() {
+    fn test_translation_does_not_affect_vectors() {
         let t = Matrix4x4::translation(5.0, -3.0, 2.0);
-        let p = Tuple4::vector(-3.0, 4.0, 5.0);
+        let v = Tuple4::vector(-3.0, 4.0, 5.0);
 
-        let result = t * p;
+        let result = t * v;
 
         assert_eq!(result, Tuple4::vector(-3.0, 4.0, 5.0));
     }
@@ -669,14 +1790,63 @@ mod tests {
         let r2 = full_quarter * p;
 
         assert_eq!(r1.x, 0.0);
-        assert!(equal(r1.y, 2.0_f64.sqrt() / 2.0));
-        assert!(equal(r1.z, 2.0_f64.sqrt() / 2.0));
+        assert!(equal(r1.y, Elem::sqrt(2.0) / 2.0));
+        assert!(equal(r1.z, Elem::sqrt(2.0) / 2.0));
 
         assert_eq!(r2.x, 0.0);
         assert!(equal(r2.y, 0.0));
         assert!(equal(r2.z, 1.0));
     }
 
+    #[test]
+    fn test_rotation_about_a_unit_axis_is_the_axis_rotation() {
+        for angle in [0.0, 0.3, PI / 4.0, PI / 2.0, 2.0, -1.2] {
+            let x = Matrix4x4::rotation_axis(Tuple4::vector(1.0, 0.0, 0.0), angle);
+            let y = Matrix4x4::rotation_axis(Tuple4::vector(0.0, 1.0, 0.0), angle);
+            let z = Matrix4x4::rotation_axis(Tuple4::vector(0.0, 0.0, 1.0), angle);
+
+            assert_eq!(x, Matrix4x4::rotation_x(angle), "angle {}", angle);
+            assert_eq!(y, Matrix4x4::rotation_y(angle), "angle {}", angle);
+            assert_eq!(z, Matrix4x4::rotation_z(angle), "angle {}", angle);
+        }
+    }
+
+    #[test]
+    fn test_rotating_a_vector_about_itself_leaves_it_unchanged() {
+        let v = Tuple4::vector(1.0, -2.0, 3.0);
+
+        for angle in [0.5, PI, 4.0] {
+            assert_approx_eq!(Matrix4x4::rotation_axis(v, angle) * v, v);
+        }
+    }
+
+    #[test]
+    fn test_rotating_about_a_diagonal_axis() {
+        let third = Matrix4x4::rotation_axis(Tuple4::vector(1.0, 1.0, 1.0), 2.0 * PI / 3.0);
+
+        assert_approx_eq!(
+            third * Tuple4::point(1.0, 0.0, 0.0),
+            Tuple4::point(0.0, 1.0, 0.0)
+        );
+        assert_approx_eq!(
+            third * Tuple4::vector(0.0, 0.0, 2.0),
+            Tuple4::vector(2.0, 0.0, 0.0)
+        );
+        assert!(third.is_rotation());
+    }
+
+    #[test]
+    #[should_panic(expected = "Rotation axis can't be zero")]
+    fn test_rotating_about_a_zero_axis_panics() {
+        Matrix4x4::rotation_axis(Tuple4::vector(0.0, 0.0, 0.0), 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rotation axis must be a vector")]
+    fn test_rotating_about_a_point_panics() {
+        Matrix4x4::rotation_axis(Tuple4::point(0.0, 1.0, 0.0), 1.0);
+    }
+
     #[test]
     fn test_inverse_of_x_rotation_rotates_in_opposite_direction() {
         let p = Tuple4::point(0.0, 1.0, 0.0);
@@ -685,8 +1855,8 @@ mod tests {
         let result = half_quarter.inverse().unwrap() * p;
 
         assert_eq!(result.x, 0.0);
-        assert!(equal(result.y, 2.0_f64.sqrt() / 2.0));
-        assert!(equal(result.z, -2.0_f64.sqrt() / 2.0));
+        assert!(equal(result.y, Elem::sqrt(2.0) / 2.0));
+        assert!(equal(result.z, -Elem::sqrt(2.0) / 2.0));
     }
 
     #[test]
@@ -698,9 +1868,9 @@ mod tests {
         let r1 = half_quarter * p;
         let r2 = full_quarter * p;
 
-        assert!(equal(r1.x, 2.0_f64.sqrt() / 2.0));
+        assert!(equal(r1.x, Elem::sqrt(2.0) / 2.0));
         assert_eq!(r1.y, 0.0);
-        assert!(equal(r1.z, 2.0_f64.sqrt() / 2.0));
+        assert!(equal(r1.z, Elem::sqrt(2.0) / 2.0));
 
         assert!(equal(r2.x, 1.0));
         assert_eq!(r2.y, 0.0);
@@ -716,13 +1886,13 @@ mod tests {
         let r1 = half_quarter * p;
         let r2 = full_quarter * p;
 
-        assert!(equal(r1.x, -2.0_f64.sqrt() / 2.0));
-        assert!(equal(r1.y, 2.0_f64.sqrt() / 2.0));
+        assert!(equal(r1.x, -Elem::sqrt(2.0) / 2.0));
+        assert!(equal(r1.y, Elem::sqrt(2.0) / 2.0));
         assert_eq!(r1.z, 0.0);
 
         assert!(equal(r2.x, -1.0));
         assert!(equal(r2.y, 0.0));
-        assert_eq!(r1.z, 0.0);
+        assert_eq!(r2.z, 0.0);
     }
 
     #[test]
@@ -785,6 +1955,225 @@ mod tests {
         assert_eq!(result, Tuple4::point(2.0, 3.0, 7.0));
     }
 
+    #[test]
+    fn test_rank_of_identity_is_four() {
+        assert_eq!(Matrix4x4::identity().rank(EPSILON), 4);
+    }
+
+    #[test]
+    fn test_rank_of_scaling_with_collapsed_axis_is_three() {
+        let m = Matrix4x4::scaling(2.0, 0.0, 3.0);
+
+        assert_eq!(m.rank(EPSILON), 3);
+    }
+
+    #[test]
+    fn test_rank_of_zero_matrix_is_zero() {
+        assert_eq!(Matrix4x4::zero().rank(EPSILON), 0);
+    }
+
+    #[test]
+    fn test_rank_of_matrix_with_dependent_rows() {
+        let m = Matrix4x4::new([
+            1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 6.0, 8.0, 0.0, 1.0, 1.0, 0.0, 1.0, 3.0, 4.0, 4.0,
+        ]);
+
+        assert_eq!(m.rank(EPSILON), 2);
+    }
+
+    #[test]
+    fn test_row_reducing_invertible_matrix_gives_identity() {
+        let m = Matrix4x4::new([
+            -5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0, 7.0, 4.0,
+        ]);
+
+        let (rref, pivots) = m.row_reduce();
+
+        assert_eq!(pivots, 4);
+        assert_approx_eq!(rref, Matrix4x4::identity(), 1e-12);
+    }
+
+    #[test]
+    fn test_generic_operations_on_3x3_matrix() {
+        let a = Matrix3x3::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        let b = Matrix3x3::from_rows([[1.0, 4.0, 7.0], [2.0, 5.0, 8.0], [3.0, 6.0, 9.0]]);
+
+        assert_eq!(a.transpose(), b);
+        assert_eq!(
+            Matrix3x3::from_cols([[1.0, 4.0, 7.0], [2.0, 5.0, 8.0], [3.0, 6.0, 9.0]]),
+            a
+        );
+        assert_eq!(a.col(1), [2.0, 5.0, 8.0]);
+        assert_eq!(a - a, Matrix3x3::zero());
+        assert_eq!(-a + a * 2.0, a);
+    }
+
+    #[test]
+    fn test_row_reducing_singular_matrix() {
+        let m = Matrix4x4::new([
+            -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0,
+        ]);
+
+        let (rref, pivots) = m.row_reduce();
+
+        assert_eq!(pivots, 3);
+        assert_eq!(rref.row(3), [0.0; 4]);
+        assert_eq!(rref.get(0, 0), 1.0);
+        assert_eq!(rref.get(1, 1), 1.0);
+        assert_eq!(rref.get(2, 2), 1.0);
+    }
+
+    #[test]
+    fn test_repairing_drifted_rotation() {
+        let step = Matrix4x4::rotation_x(0.001) * Matrix4x4::rotation_y(0.002);
+        let mut m = Matrix4x4::translation(1.0, 2.0, 3.0);
+        for i in 0..10_000 {
+            m = step * m;
+            m.data[i % 11 / 4][i % 11 % 4] += 1e-7;
+        }
+        assert!(!m.is_rotation());
+
+        let repaired = m.orthonormalize();
+
+        assert!(repaired.is_rotation());
+        assert!(repaired.rotation_error() < tolerance(1e-12));
+        for y in 0..3 {
+            assert_eq!(repaired.get(y, 3), m.get(y, 3));
+        }
+    }
+
+    #[test]
+    fn test_repairing_exact_rotation_is_a_no_op() {
+        let m = Matrix4x4::rotation_z(0.7) * Matrix4x4::rotation_x(-1.3);
+
+        let repaired = m.orthonormalize();
+
+        assert!(m.is_rotation());
+        assert_approx_eq!(repaired, m, 1e-12);
+    }
+
+    #[test]
+    fn test_repairing_reflection_preserves_handedness() {
+        let m = Matrix4x4::scaling(-1.0, 1.0, 1.0)
+            * Matrix4x4::rotation_y(0.4)
+            * Matrix4x4::scaling(1.0, 1.0 + 1e-6, 1.0);
+
+        let repaired = m.orthonormalize();
+
+        assert!(repaired.rotation_error() < tolerance(1e-12));
+        assert!(!repaired.is_rotation());
+        assert!(equal(repaired.det(), -1.0));
+    }
+
+    fn recompose(translation: Tuple4, rotation: Matrix4x4, scale: Tuple4) -> Matrix4x4 {
+        Matrix4x4::translation(translation.x, translation.y, translation.z)
+            * rotation
+            * Matrix4x4::scaling(scale.x, scale.y, scale.z)
+    }
+
+    #[test]
+    fn test_decomposing_a_composed_transform() {
+        let m = Matrix4x4::translation(1.0, -2.0, 3.0)
+            * Matrix4x4::rotation_y(0.5)
+            * Matrix4x4::rotation_x(-1.2)
+            * Matrix4x4::scaling(2.0, 0.5, 3.0);
+
+        let (t, r, s) = m.decompose().unwrap();
+
+        assert_approx_eq!(t, Tuple4::vector(1.0, -2.0, 3.0), tolerance(1e-9));
+        assert_approx_eq!(
+            r,
+            Matrix4x4::rotation_y(0.5) * Matrix4x4::rotation_x(-1.2),
+            tolerance(1e-9)
+        );
+        assert_approx_eq!(s, Tuple4::vector(2.0, 0.5, 3.0), tolerance(1e-9));
+        assert_approx_eq!(recompose(t, r, s), m, tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_decomposing_identity() {
+        let (t, r, s) = Matrix4x4::identity().decompose().unwrap();
+
+        assert_eq!(t, Tuple4::vector(0.0, 0.0, 0.0));
+        assert_eq!(r, Matrix4x4::identity());
+        assert_eq!(s, Tuple4::vector(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_decomposing_a_reflection_puts_the_sign_on_x() {
+        let m = Matrix4x4::translation(0.0, 4.0, 0.0)
+            * Matrix4x4::rotation_z(0.3)
+            * Matrix4x4::scaling(2.0, -3.0, 4.0);
+
+        let (t, r, s) = m.decompose().unwrap();
+
+        assert!(r.is_rotation());
+        assert_approx_eq!(s, Tuple4::vector(-2.0, 3.0, 4.0), tolerance(1e-9));
+        assert_approx_eq!(
+            r,
+            Matrix4x4::rotation_z(0.3) * Matrix4x4::rotation_z(PI),
+            tolerance(1e-9)
+        );
+        assert_approx_eq!(recompose(t, r, s), m, tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_decomposing_degenerate_matrices() {
+        let mut projective = Matrix4x4::identity();
+        projective.set(3, 2, 1.0);
+
+        assert!(Matrix4x4::scaling(1.0, 0.0, 1.0).decompose().is_none());
+        assert!(Matrix4x4::shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+            .decompose()
+            .is_none());
+        assert!(projective.decompose().is_none());
+    }
+
+    #[test]
+    fn test_interpolated_transform_hits_the_endpoints() {
+        let a = Matrix4x4::translation(1.0, 0.0, -2.0) * Matrix4x4::rotation_x(0.3);
+        let b = Matrix4x4::translation(-4.0, 2.0, 5.0)
+            * Matrix4x4::rotation_z(2.0)
+            * Matrix4x4::rotation_y(-0.8)
+            * Matrix4x4::scaling(1.0, 3.0, 0.5);
+
+        assert_approx_eq!(a.lerp_transform(&b, 0.0), a, tolerance(1e-9));
+        assert_approx_eq!(a.lerp_transform(&b, 1.0), b, tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_interpolating_rotations_about_one_axis_stays_on_it() {
+        let a = Matrix4x4::translation(0.0, 1.0, 0.0) * Matrix4x4::rotation_y(0.2);
+        let b = Matrix4x4::translation(4.0, 1.0, 0.0)
+            * Matrix4x4::rotation_y(1.4)
+            * Matrix4x4::scaling(3.0, 3.0, 3.0);
+
+        for i in 0..=10 {
+            let t = i as Elem / 10.0;
+            let expected = Matrix4x4::translation(4.0 * t, 1.0, 0.0)
+                * Matrix4x4::rotation_y(0.2 + 1.2 * t)
+                * Matrix4x4::scaling(1.0 + 2.0 * t, 1.0 + 2.0 * t, 1.0 + 2.0 * t);
+
+            assert_approx_eq!(a.lerp_transform(&b, t), expected);
+        }
+    }
+
+    #[test]
+    fn test_interpolating_sheared_transforms_is_elementwise() {
+        let a = Matrix4x4::shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        let b = Matrix4x4::identity();
+
+        assert_approx_eq!(a.lerp_transform(&b, 0.5), a * 0.5 + b * 0.5);
+    }
+
+    #[test]
+    fn test_rotation_error_of_scaling() {
+        let m = Matrix4x4::scaling(2.0, 1.0, 1.0);
+
+        assert!(equal(m.rotation_error(), 3.0));
+        assert!(!m.is_rotation());
+    }
+
     #[test]
     fn test_individual_transformations_applied_in_sequence() {
         let p = Tuple4::point(1.0, 0.0, 1.0);
@@ -793,13 +2182,13 @@ mod tests {
         let c = Matrix4x4::translation(10.0, 5.0, 7.0);
 
         let p2 = a * p;
-        assert!(tuples_equal(&p2, &Tuple4::point(1.0, -1.0, 0.0)));
+        assert_approx_eq!(p2, Tuple4::point(1.0, -1.0, 0.0));
 
         let p3 = b * p2;
-        assert!(tuples_equal(&p3, &Tuple4::point(5.0, -5.0, 0.0)));
+        assert_approx_eq!(p3, Tuple4::point(5.0, -5.0, 0.0));
 
         let p4 = c * p3;
-        assert!(tuples_equal(&p4, &Tuple4::point(15.0, 0.0, 7.0)));
+        assert_approx_eq!(p4, Tuple4::point(15.0, 0.0, 7.0));
     }
 
     #[test]
@@ -812,6 +2201,107 @@ mod tests {
 
         let result = t * p;
 
-        assert!(tuples_equal(&result, &Tuple4::point(15.0, 0.0, 7.0)));
+        assert_approx_eq!(result, Tuple4::point(15.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn test_fluent_transformations_apply_in_reading_order() {
+        let p = Tuple4::point(1.0, 0.0, 1.0);
+        let a = Matrix4x4::rotation_x(PI / 2.0);
+        let b = Matrix4x4::scaling(5.0, 5.0, 5.0);
+        let c = Matrix4x4::translation(10.0, 5.0, 7.0);
+
+        let t = Matrix4x4::identity()
+            .rotate_x(PI / 2.0)
+            .scale(5.0, 5.0, 5.0)
+            .translate(10.0, 5.0, 7.0);
+
+        assert_eq!(t, c * b * a);
+        assert_approx_eq!(t * p, Tuple4::point(15.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn test_fluent_transformations_match_their_constructors() {
+        let m = Matrix4x4::translation(1.0, 2.0, 3.0);
+
+        assert_eq!(m.rotate_y(0.5), Matrix4x4::rotation_y(0.5) * m);
+        assert_eq!(m.rotate_z(0.5), Matrix4x4::rotation_z(0.5) * m);
+        let axis = Tuple4::vector(1.0, 1.0, 0.0);
+        assert_eq!(
+            m.rotate_axis(axis, 0.5),
+            Matrix4x4::rotation_axis(axis, 0.5) * m
+        );
+        assert_eq!(
+            m.shear(1.0, 2.0, 3.0, 4.0, 5.0, 6.0),
+            Matrix4x4::shearing(1.0, 2.0, 3.0, 4.0, 5.0, 6.0) * m
+        );
+    }
+
+    #[test]
+    fn test_view_transform_for_the_default_orientation() {
+        let from = Tuple4::point(0.0, 0.0, 0.0);
+        let to = Tuple4::point(0.0, 0.0, -1.0);
+        let up = Tuple4::vector(0.0, 1.0, 0.0);
+
+        let t = Matrix4x4::view_transform(from, to, up);
+
+        assert_eq!(t, Matrix4x4::identity());
+    }
+
+    #[test]
+    fn test_view_transform_looking_in_positive_z_direction() {
+        let from = Tuple4::point(0.0, 0.0, 0.0);
+        let to = Tuple4::point(0.0, 0.0, 1.0);
+        let up = Tuple4::vector(0.0, 1.0, 0.0);
+
+        let t = Matrix4x4::view_transform(from, to, up);
+
+        assert_eq!(t, Matrix4x4::scaling(-1.0, 1.0, -1.0));
+    }
+
+    #[test]
+    fn test_view_transform_moves_the_world() {
+        let from = Tuple4::point(0.0, 0.0, 8.0);
+        let to = Tuple4::point(0.0, 0.0, 0.0);
+        let up = Tuple4::vector(0.0, 1.0, 0.0);
+
+        let t = Matrix4x4::view_transform(from, to, up);
+
+        assert_eq!(t, Matrix4x4::translation(0.0, 0.0, -8.0));
+    }
+
+    #[test]
+    fn test_arbitrary_view_transform() {
+        let from = Tuple4::point(1.0, 3.0, 2.0);
+        let to = Tuple4::point(4.0, -2.0, 8.0);
+        let up = Tuple4::vector(1.0, 1.0, 0.0);
+        let expected = [
+            -0.50709, 0.50709, 0.67612, -2.36643, 0.76772, 0.60609, 0.12122, -2.82843, -0.35857,
+            0.59761, -0.71714, 0.00000, 0.00000, 0.00000, 0.00000, 1.00000,
+        ];
+
+        let t = Matrix4x4::view_transform(from, to, up);
+
+        for (i, e) in expected.into_iter().enumerate() {
+            let (y, x) = (i / 4, i % 4);
+            assert!((t.get(y, x) - e).abs() < 1e-5, "({}, {})", y, x);
+        }
+    }
+
+    #[test]
+    fn test_view_transform_up_needs_no_normalizing_or_exact_perpendicular() {
+        let from = Tuple4::point(1.0, 3.0, 2.0);
+        let to = Tuple4::point(4.0, -2.0, 8.0);
+        let up = Tuple4::vector(0.0, 1.0, 0.0);
+        let skewed_up = Tuple4::vector(0.3, 7.0, 0.2);
+
+        let t1 = Matrix4x4::view_transform(from, to, up);
+        let t2 = Matrix4x4::view_transform(from, to, up * 10.0);
+        let t3 = Matrix4x4::view_transform(from, to, skewed_up);
+
+        assert_approx_eq!(t1, t2);
+        let forward = Tuple4::vector(0.0, 0.0, -1.0);
+        let looking = t3.inverse().unwrap() * forward;
+        assert_approx_eq!(looking, (to - from).normalize());
     }
 }
@@ -0,0 +1,163 @@
+use crate::intersection::Intersection;
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::shape::{Shape, PARALLEL_EPSILON};
+use crate::transform::Transform;
+use crate::tuple::Tuple4;
+
+/// The infinite xz plane through the origin, facing +y.
+#[derive(PartialEq)]
+pub struct Plane {
+    transform: Transform,
+    material: Material,
+}
+
+impl Plane {
+    pub fn new() -> Plane {
+        Plane {
+            transform: Transform::identity(),
+            material: Material::default(),
+        }
+    }
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shape for Plane {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    /// Rays parallel to the plane, including those lying in it, miss,
+    /// judged relative to the length of the direction.
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let d = ray.direction;
+        if d.y * d.y <= PARALLEL_EPSILON * d.dot(&d) {
+            return Vec::new();
+        }
+
+        let t = -ray.origin.y / ray.direction.y;
+        vec![Intersection::new(t, self)]
+    }
+
+    fn local_normal_at(&self, _: Tuple4) -> Tuple4 {
+        Tuple4::vector(0.0, 1.0, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::matrix::Matrix4x4;
+
+    #[test]
+    fn test_the_normal_of_a_plane_is_constant_everywhere() {
+        let p = Plane::new();
+
+        for point in [
+            Tuple4::point(0.0, 0.0, 0.0),
+            Tuple4::point(10.0, 0.0, -10.0),
+            Tuple4::point(-5.0, 0.0, 150.0),
+        ] {
+            assert_eq!(p.local_normal_at(point), Tuple4::vector(0.0, 1.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_intersect_with_a_ray_parallel_to_the_plane() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple4::point(0.0, 10.0, 0.0), Tuple4::vector(0.0, 0.0, 1.0));
+
+        let xs = p.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn test_intersect_with_a_coplanar_ray() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple4::point(0.0, 0.0, 0.0), Tuple4::vector(0.0, 0.0, 1.0));
+
+        let xs = p.local_intersect(&r);
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn test_a_ray_intersecting_a_plane_from_above() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple4::point(0.0, 1.0, 0.0), Tuple4::vector(0.0, -1.0, 0.0));
+
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+        assert!(ptr::addr_eq(xs[0].object, &p));
+    }
+
+    #[test]
+    fn test_a_ray_intersecting_a_plane_from_below() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple4::point(0.0, -1.0, 0.0), Tuple4::vector(0.0, 1.0, 0.0));
+
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+        assert!(ptr::addr_eq(xs[0].object, &p));
+    }
+
+    #[test]
+    fn test_intersecting_a_transformed_plane() {
+        let mut p = Plane::new();
+        p.set_transform(Matrix4x4::translation(0.0, 2.0, 0.0).rotate_x(0.3));
+        let r = Ray::new(
+            Tuple4::point(0.0, 10.0, 0.0),
+            Tuple4::vector(0.0, -1.0, 0.0),
+        );
+
+        let xs = p.intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        let hit = r.position(xs[0].t);
+        let normal = p.normal_at(hit);
+        assert_approx_eq!(
+            normal,
+            Matrix4x4::rotation_x(0.3) * Tuple4::vector(0.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_intersecting_a_scaled_plane() {
+        let mut p = Plane::new();
+        p.set_transform(Matrix4x4::scaling(2e6, 2e6, 2e6));
+        let r = Ray::new(
+            Tuple4::point(0.0, 10.0, 0.0),
+            Tuple4::vector(0.0, -1.0, 0.0),
+        );
+
+        let xs = p.intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 10.0);
+    }
+}
@@ -1,4 +1,9 @@
-use std::io::{self, Write};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt,
+    io::{self, BufRead, Write},
+};
 
 pub trait RGB {
     fn r(&self) -> u8;
@@ -6,10 +11,189 @@ pub trait RGB {
     fn b(&self) -> u8;
 }
 
-pub trait PPM<T> {
+/// Maps a linear channel value in `0.0..=1.0` to `0..=255`.
+///
+/// Values are clamped first and then rounded half away from zero, so
+/// `k / 510.0` lands on `(k + 1) / 2` for odd `k` on every platform. NaN
+/// maps to 0.
+pub fn channel_to_u8(n: f64) -> u8 {
+    (n * 255.0).clamp(0.0, 255.0).round() as u8
+}
+
+/// Packs colors into consecutive `r, g, b` bytes, as binary PPM and PNG
+/// store them.
+pub(crate) fn rgb_bytes<H: RGB>(colors: &[H]) -> Vec<u8> {
+    colors.iter().flat_map(|c| [c.r(), c.g(), c.b()]).collect()
+}
+
+#[derive(Debug)]
+pub enum PpmParseError {
+    Io(io::Error),
+    BadMagic { found: String },
+    InvalidNumber { token: String },
+    InvalidMaxValue { value: u32 },
+    ValueAboveMax { value: u32, max: u32 },
+    UnexpectedEnd,
+}
+
+impl fmt::Display for PpmParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PpmParseError::Io(e) => write!(f, "failed to read image: {}", e),
+            PpmParseError::BadMagic { found } => {
+                write!(f, "expected `P3` or `P6`, found `{}`", found)
+            }
+            PpmParseError::InvalidNumber { token } => write!(f, "invalid number `{}`", token),
+            PpmParseError::InvalidMaxValue { value } => {
+                write!(f, "maximum color value {} is not in 1..=65535", value)
+            }
+            PpmParseError::ValueAboveMax { value, max } => {
+                write!(f, "color value {} exceeds the maximum {}", value, max)
+            }
+            PpmParseError::UnexpectedEnd => write!(f, "unexpected end of image data"),
+        }
+    }
+}
+
+impl Error for PpmParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PpmParseError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PpmParseError {
+    fn from(e: io::Error) -> Self {
+        PpmParseError::Io(e)
+    }
+}
+
+/// A decoded PPM: its size and row-major `[r, g, b]` channels scaled to
+/// `0.0..=1.0`.
+pub(crate) struct DecodedPpm {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[f64; 3]>,
+}
+
+struct Scanner {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl Scanner {
+    /// The next whitespace-separated token, skipping `#` comments.
+    fn token(&mut self) -> Option<String> {
+        loop {
+            match self.data.get(self.pos)? {
+                b'#' => {
+                    while self.data.get(self.pos).is_some_and(|&c| c != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                c if c.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|c| !c.is_ascii_whitespace() && *c != b'#')
+        {
+            self.pos += 1;
+        }
+        Some(String::from_utf8_lossy(&self.data[start..self.pos]).into_owned())
+    }
+
+    fn number(&mut self) -> Result<u32, PpmParseError> {
+        let token = self.token().ok_or(PpmParseError::UnexpectedEnd)?;
+        token
+            .parse()
+            .map_err(|_| PpmParseError::InvalidNumber { token })
+    }
+
+    /// A raw P6 sample, one byte or two big-endian bytes depending on `max`.
+    fn sample(&mut self, max: u32) -> Result<u32, PpmParseError> {
+        let width = if max < 256 { 1 } else { 2 };
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + width)
+            .ok_or(PpmParseError::UnexpectedEnd)?;
+        self.pos += width;
+        Ok(bytes.iter().fold(0, |n, &b| n << 8 | b as u32))
+    }
+}
+
+/// Decodes a text P3 or binary P6 PPM.
+///
+/// Comments run from `#` to the end of the line and may appear anywhere
+/// in a P3 file, but only in the header of a P6 one.
+pub(crate) fn decode<R: BufRead>(mut reader: R) -> Result<DecodedPpm, PpmParseError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    let mut scanner = Scanner { data, pos: 0 };
+
+    let magic = scanner.token().ok_or(PpmParseError::UnexpectedEnd)?;
+    let binary = match magic.as_str() {
+        "P3" => false,
+        "P6" => true,
+        _ => return Err(PpmParseError::BadMagic { found: magic }),
+    };
+    let width = scanner.number()? as usize;
+    let height = scanner.number()? as usize;
+    let max = scanner.number()?;
+    if !(1..=65535).contains(&max) {
+        return Err(PpmParseError::InvalidMaxValue { value: max });
+    }
+    if binary {
+        // A single whitespace byte separates the header from the samples.
+        scanner.pos += 1;
+    }
+
+    // The header is only a claim: a count that doesn't fit in memory can't
+    // be backed by the data, and every pixel needs at least a byte of it, so
+    // that bounds what is worth reserving.
+    let count = width
+        .checked_mul(height)
+        .ok_or(PpmParseError::UnexpectedEnd)?;
+    let capacity = count.min(scanner.data.len().saturating_sub(scanner.pos));
+
+    let mut channel = || {
+        let value = if binary {
+            scanner.sample(max)?
+        } else {
+            scanner.number()?
+        };
+        if value > max {
+            return Err(PpmParseError::ValueAboveMax { value, max });
+        }
+        Ok(value as f64 / max as f64)
+    };
+
+    let mut pixels = Vec::with_capacity(capacity);
+    for _ in 0..count {
+        pixels.push([channel()?, channel()?, channel()?]);
+    }
+
+    Ok(DecodedPpm {
+        width,
+        height,
+        pixels,
+    })
+}
+
+pub trait PPM<T: Clone> {
     fn width(&self) -> usize;
     fn height(&self) -> usize;
-    fn colors(&self) -> &[T];
+
+    /// Row `y`, left to right. Encoders ask for one row at a time, so an
+    /// image stored in some other form only ever converts a row's worth of
+    /// pixels.
+    fn row(&self, y: usize) -> Cow<'_, [T]>;
 }
 
 pub struct PPMEncoder<'a, T: Write> {
@@ -18,26 +202,29 @@ pub struct PPMEncoder<'a, T: Write> {
 
 impl<'a, T: Write> PPMEncoder<'a, T> {
     const PPM_HEADER: &'static str = "P3";
+    const PPM_BINARY_HEADER: &'static str = "P6";
     const PPM_MAX: &'static str = "255";
 
     pub fn new(writer: &'a mut T) -> Self {
         PPMEncoder { writer }
     }
 
-    fn write_header(&mut self, width: usize, height: usize) -> io::Result<()> {
-        let header = format!(
-            "{}\n{} {}\n{}\n",
-            Self::PPM_HEADER,
-            width,
-            height,
-            Self::PPM_MAX
-        );
+    fn write_header(&mut self, magic: &str, width: usize, height: usize) -> io::Result<()> {
+        let header = format!("{}\n{} {}\n{}\n", magic, width, height, Self::PPM_MAX);
         self.writer.write_all(header.as_bytes())
     }
 
-    fn write_data<H: RGB>(&mut self, width: usize, colors: &[H]) -> io::Result<()> {
-        for (i, color) in colors.iter().enumerate() {
-            let s = if (i + 1) % width == 0 {
+    fn write_data<H: RGB + Clone, P: PPM<H>>(&mut self, ppm: &P) -> io::Result<()> {
+        for y in 0..ppm.height() {
+            self.write_row(&ppm.row(y))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_row<H: RGB>(&mut self, row: &[H]) -> io::Result<()> {
+        for (i, color) in row.iter().enumerate() {
+            let s = if i + 1 == row.len() {
                 format!("{} {} {}\n", color.r(), color.g(), color.b())
             } else {
                 format!("{} {} {} ", color.r(), color.g(), color.b())
@@ -49,12 +236,86 @@ impl<'a, T: Write> PPMEncoder<'a, T> {
         Ok(())
     }
 
-    pub fn write<H: RGB, P: PPM<H>>(&mut self, ppm: &P) -> io::Result<()> {
-        self.write_header(ppm.width(), ppm.height())?;
-        self.write_data(ppm.width(), ppm.colors())?;
+    pub fn write<H: RGB + Clone, P: PPM<H>>(&mut self, ppm: &P) -> io::Result<()> {
+        self.write_header(Self::PPM_HEADER, ppm.width(), ppm.height())?;
+        self.write_data(ppm)?;
 
         Ok(())
     }
+
+    /// Writes the binary P6 variant: the same header, then three raw bytes
+    /// per pixel, quantized exactly like [`PPMEncoder::write`].
+    pub fn write_binary<H: RGB + Clone, P: PPM<H>>(&mut self, ppm: &P) -> io::Result<()> {
+        self.write_header(Self::PPM_BINARY_HEADER, ppm.width(), ppm.height())?;
+        for y in 0..ppm.height() {
+            self.writer.write_all(&rgb_bytes(&ppm.row(y)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a text P3 PPM one row at a time, so the image never has to be
+/// held in memory. The output matches [`PPMEncoder::write`] byte for byte.
+pub struct PpmStreamWriter<W: Write> {
+    writer: W,
+    width: usize,
+    height: usize,
+    rows_written: usize,
+}
+
+impl<W: Write> PpmStreamWriter<W> {
+    /// Writes the header straight away.
+    pub fn new(mut writer: W, width: usize, height: usize) -> io::Result<Self> {
+        PPMEncoder::new(&mut writer).write_header(PPMEncoder::<W>::PPM_HEADER, width, height)?;
+
+        Ok(PpmStreamWriter {
+            writer,
+            width,
+            height,
+            rows_written: 0,
+        })
+    }
+
+    /// Writes and flushes the next row. Fails with `InvalidInput` if the
+    /// row isn't `width` pixels long or all `height` rows are already
+    /// written.
+    pub fn write_row<H: RGB>(&mut self, row: &[H]) -> io::Result<()> {
+        if row.len() != self.width {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected a row of {} pixels, got {}", self.width, row.len()),
+            ));
+        }
+        if self.rows_written == self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("all {} rows have already been written", self.height),
+            ));
+        }
+
+        PPMEncoder::new(&mut self.writer).write_row(row)?;
+        self.writer.flush()?;
+        self.rows_written += 1;
+
+        Ok(())
+    }
+
+    /// Returns the inner writer, failing with `UnexpectedEof` if fewer than
+    /// `height` rows were written.
+    pub fn finish(self) -> io::Result<W> {
+        if self.rows_written != self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "only {} of {} rows were written",
+                    self.rows_written, self.height
+                ),
+            ));
+        }
+
+        Ok(self.writer)
+    }
 }
 
 #[cfg(test)]
@@ -93,17 +354,61 @@ mod tests {
             self.height
         }
 
-        fn colors(&self) -> &[Tuple3] {
-            &self.colors
+        fn row(&self, y: usize) -> Cow<'_, [Tuple3]> {
+            Cow::Borrowed(&self.colors[y * self.width..(y + 1) * self.width])
+        }
+    }
+
+    #[test]
+    fn test_channel_to_u8_is_monotonic() {
+        let values: Vec<_> = (0..=510).map(|k| channel_to_u8(k as f64 / 510.0)).collect();
+
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(values[0], 0);
+        assert_eq!(values[510], 255);
+    }
+
+    #[test]
+    fn test_channel_to_u8_rounds_half_away_from_zero() {
+        for k in 0..=510 {
+            let expected = (k + k % 2) / 2;
+
+            assert_eq!(channel_to_u8(k as f64 / 510.0), expected as u8, "k = {}", k);
         }
     }
 
+    #[test]
+    fn test_channel_to_u8_boundary_values() {
+        let table = [
+            (0.0, 0),
+            (1.0 / 510.0, 1),
+            (0.5, 128),
+            (0.5 - 1.0 / 510.0, 127),
+            (509.0 / 510.0, 255),
+            (254.0 / 255.0, 254),
+            (1.0, 255),
+        ];
+
+        for (n, expected) in table {
+            assert_eq!(channel_to_u8(n), expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_channel_to_u8_clamps_out_of_range_values() {
+        assert_eq!(channel_to_u8(-0.5), 0);
+        assert_eq!(channel_to_u8(-f64::INFINITY), 0);
+        assert_eq!(channel_to_u8(1.5), 255);
+        assert_eq!(channel_to_u8(f64::INFINITY), 255);
+        assert_eq!(channel_to_u8(f64::NAN), 0);
+    }
+
     #[test]
     fn test_to_ppm_header() {
         let c = Canvas {
             width: 5,
             height: 3,
-            colors: Vec::new(),
+            colors: vec![Tuple3(0, 0, 0); 15],
         };
         let mut buffer = Vec::new();
         let mut encoder = PPMEncoder::new(&mut buffer);
@@ -111,7 +416,59 @@ mod tests {
         encoder.write(&c).unwrap();
 
         let s = String::from_utf8(buffer).unwrap();
-        assert_eq!("P3\n5 3\n255\n", &s);
+        assert!(s.starts_with("P3\n5 3\n255\n"));
+    }
+
+    #[test]
+    fn test_to_binary_ppm() {
+        let mut c = Canvas {
+            width: 2,
+            height: 1,
+            colors: vec![Tuple3(0, 0, 0); 2],
+        };
+        c.colors[1] = Tuple3(255, 128, 10);
+        let mut buffer = Vec::new();
+        let mut encoder = PPMEncoder::new(&mut buffer);
+
+        encoder.write_binary(&c).unwrap();
+
+        assert_eq!(&buffer[..11], b"P6\n2 1\n255\n");
+        assert_eq!(&buffer[11..], &[0, 0, 0, 255, 128, 10]);
+    }
+
+    #[test]
+    fn test_streaming_matches_the_encoder() {
+        let c = Canvas {
+            width: 3,
+            height: 2,
+            colors: (0..6).map(|i| Tuple3(i, 100 + i, 255 - i)).collect(),
+        };
+        let mut expected = Vec::new();
+        PPMEncoder::new(&mut expected).write(&c).unwrap();
+        let mut stream = PpmStreamWriter::new(Vec::new(), 3, 2).unwrap();
+
+        for row in c.colors.chunks(3) {
+            stream.write_row(row).unwrap();
+        }
+
+        assert_eq!(stream.finish().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_streaming_enforces_the_image_size() {
+        let row = [Tuple3(0, 0, 0); 2];
+        let mut stream = PpmStreamWriter::new(Vec::new(), 2, 1).unwrap();
+
+        let err = stream.write_row(&row[..1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        stream.write_row(&row).unwrap();
+        let err = stream.write_row(&row).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let short = PpmStreamWriter::new(Vec::new(), 2, 2).unwrap();
+        let err = short.finish().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(err.to_string(), "only 0 of 2 rows were written");
     }
 
     #[test]
@@ -0,0 +1,311 @@
+use std::ops::Mul;
+
+use crate::{
+    matrix::Matrix4x4,
+    tuple::{Elem, Tuple4},
+};
+
+/// Above this dot product two rotations are close enough that `slerp`
+/// falls back to a normalized lerp instead of dividing by a vanishing sine.
+const SLERP_LERP_THRESHOLD: Elem = 0.9995;
+
+/// A rotation stored as `w + xi + yj + zk`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Quaternion {
+    pub w: Elem,
+    pub x: Elem,
+    pub y: Elem,
+    pub z: Elem,
+}
+
+impl Quaternion {
+    pub fn new(w: Elem, x: Elem, y: Elem, z: Elem) -> Self {
+        Quaternion { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Rotation by `angle` radians about `axis`, which doesn't need to be
+    /// normalized.
+    pub fn from_axis_angle(axis: Tuple4, angle: Elem) -> Self {
+        assert!(axis.is_vector(), "Rotation axis must be a vector");
+        assert!(axis.magnitude() > 0.0, "Rotation axis can't be zero");
+
+        let axis = axis.normalize();
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        Quaternion::new(cos, axis.x * sin, axis.y * sin, axis.z * sin)
+    }
+
+    /// The quaternion of the rotation in the upper-left 3x3 of `m`, which
+    /// must be a proper rotation. Branches on the largest diagonal term to
+    /// keep the square root away from zero.
+    pub fn from_matrix(m: &Matrix4x4) -> Self {
+        let trace = m.get(0, 0) + m.get(1, 1) + m.get(2, 2);
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion::new(
+                s / 4.0,
+                (m.get(2, 1) - m.get(1, 2)) / s,
+                (m.get(0, 2) - m.get(2, 0)) / s,
+                (m.get(1, 0) - m.get(0, 1)) / s,
+            )
+        } else if m.get(0, 0) > m.get(1, 1) && m.get(0, 0) > m.get(2, 2) {
+            let s = (1.0 + m.get(0, 0) - m.get(1, 1) - m.get(2, 2)).sqrt() * 2.0;
+            Quaternion::new(
+                (m.get(2, 1) - m.get(1, 2)) / s,
+                s / 4.0,
+                (m.get(0, 1) + m.get(1, 0)) / s,
+                (m.get(0, 2) + m.get(2, 0)) / s,
+            )
+        } else if m.get(1, 1) > m.get(2, 2) {
+            let s = (1.0 + m.get(1, 1) - m.get(0, 0) - m.get(2, 2)).sqrt() * 2.0;
+            Quaternion::new(
+                (m.get(0, 2) - m.get(2, 0)) / s,
+                (m.get(0, 1) + m.get(1, 0)) / s,
+                s / 4.0,
+                (m.get(1, 2) + m.get(2, 1)) / s,
+            )
+        } else {
+            let s = (1.0 + m.get(2, 2) - m.get(0, 0) - m.get(1, 1)).sqrt() * 2.0;
+            Quaternion::new(
+                (m.get(1, 0) - m.get(0, 1)) / s,
+                (m.get(0, 2) + m.get(2, 0)) / s,
+                (m.get(1, 2) + m.get(2, 1)) / s,
+                s / 4.0,
+            )
+        };
+
+        q.normalize()
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> Elem {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn magnitude(&self) -> Elem {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        let m = self.magnitude();
+        Quaternion::new(self.w / m, self.x / m, self.y / m, self.z / m)
+    }
+
+    /// Interpolates along the shorter arc between two unit quaternions.
+    ///
+    /// `t = 0` and `t = 1` return `self` and `other` exactly. Nearly equal
+    /// rotations are blended with a normalized lerp.
+    pub fn slerp(self, other: Quaternion, t: Elem) -> Self {
+        if t == 0.0 {
+            return self;
+        }
+        if t == 1.0 {
+            return other;
+        }
+
+        let mut cos = self.dot(&other);
+        let mut other = other;
+        if cos < 0.0 {
+            cos = -cos;
+            other = Quaternion::new(-other.w, -other.x, -other.y, -other.z);
+        }
+
+        let (a, b) = if cos > SLERP_LERP_THRESHOLD {
+            (1.0 - t, t)
+        } else {
+            let theta = cos.acos();
+            let sin = theta.sin();
+            (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+        };
+
+        Quaternion::new(
+            a * self.w + b * other.w,
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+        )
+        .normalize()
+    }
+
+    /// The rotation matrix of this quaternion, which must be normalized.
+    pub fn to_matrix(&self) -> Matrix4x4 {
+        let Quaternion { w, x, y, z } = *self;
+
+        Matrix4x4::new([
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y - w * z),
+            2.0 * (x * z + w * y),
+            0.0,
+            2.0 * (x * y + w * z),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - w * x),
+            0.0,
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            1.0 - 2.0 * (x * x + y * y),
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+        ])
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+impl From<Quaternion> for Matrix4x4 {
+    fn from(q: Quaternion) -> Self {
+        q.to_matrix()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tuple::consts::PI;
+
+    use super::*;
+    use crate::{approx::tolerance, assert_approx_eq};
+
+    const EPSILON: Elem = tolerance(1e-9);
+
+    fn quaternions_equal(a: &Quaternion, b: &Quaternion) -> bool {
+        (a.w - b.w).abs() < EPSILON
+            && (a.x - b.x).abs() < EPSILON
+            && (a.y - b.y).abs() < EPSILON
+            && (a.z - b.z).abs() < EPSILON
+    }
+
+    #[test]
+    fn test_axis_angle_agrees_with_cardinal_rotations() {
+        for angle in [0.0, PI / 4.0, PI / 2.0, 2.0, -1.0, PI] {
+            let x = Quaternion::from_axis_angle(Tuple4::vector(1.0, 0.0, 0.0), angle);
+            let y = Quaternion::from_axis_angle(Tuple4::vector(0.0, 1.0, 0.0), angle);
+            let z = Quaternion::from_axis_angle(Tuple4::vector(0.0, 0.0, 1.0), angle);
+
+            assert_approx_eq!(x.to_matrix(), Matrix4x4::rotation_x(angle), EPSILON);
+            assert_approx_eq!(y.to_matrix(), Matrix4x4::rotation_y(angle), EPSILON);
+            assert_approx_eq!(Matrix4x4::from(z), Matrix4x4::rotation_z(angle), EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_through_matrix() {
+        let rotations = [
+            Matrix4x4::identity(),
+            Matrix4x4::rotation_x(0.4),
+            Matrix4x4::rotation_y(PI - 0.01),
+            Matrix4x4::rotation_z(PI),
+            Matrix4x4::rotation_x(PI) * Matrix4x4::rotation_y(0.3),
+            Matrix4x4::rotation_z(-2.5) * Matrix4x4::rotation_x(1.1) * Matrix4x4::rotation_y(2.9),
+        ];
+
+        for m in rotations {
+            assert_approx_eq!(Quaternion::from_matrix(&m).to_matrix(), m, EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_axis_is_normalized() {
+        let q = Quaternion::from_axis_angle(Tuple4::vector(0.0, 5.0, 0.0), 1.0);
+
+        assert_approx_eq!(q.to_matrix(), Matrix4x4::rotation_y(1.0), EPSILON);
+    }
+
+    #[test]
+    fn test_axis_angle_agrees_with_rotation_axis() {
+        let axis = Tuple4::vector(-0.3, 2.0, 0.7);
+
+        let q = Quaternion::from_axis_angle(axis, 1.1);
+
+        assert_approx_eq!(q.to_matrix(), Matrix4x4::rotation_axis(axis, 1.1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rotation axis can't be zero")]
+    fn test_zero_axis_panics() {
+        Quaternion::from_axis_angle(Tuple4::vector(0.0, 0.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_multiplying_composes_rotations() {
+        let a = Quaternion::from_axis_angle(Tuple4::vector(1.0, 0.0, 0.0), PI / 2.0);
+        let b = Quaternion::from_axis_angle(Tuple4::vector(0.0, 0.0, 1.0), PI / 3.0);
+
+        let expected = Matrix4x4::rotation_z(PI / 3.0) * Matrix4x4::rotation_x(PI / 2.0);
+
+        assert_approx_eq!((b * a).to_matrix(), expected, EPSILON);
+    }
+
+    #[test]
+    fn test_normalizing_a_quaternion() {
+        let q = Quaternion::new(2.0, 0.0, 0.0, 0.0).normalize();
+
+        assert_eq!(q, Quaternion::identity());
+    }
+
+    #[test]
+    fn test_slerp_returns_endpoints_exactly() {
+        let a = Quaternion::from_axis_angle(Tuple4::vector(1.0, 2.0, 3.0), 0.3);
+        let b = Quaternion::from_axis_angle(Tuple4::vector(-1.0, 0.5, 0.0), 2.1);
+
+        assert_eq!(a.slerp(b, 0.0), a);
+        assert_eq!(a.slerp(b, 1.0), b);
+    }
+
+    #[test]
+    fn test_slerp_halfway_is_half_the_angle() {
+        let axis = Tuple4::vector(0.0, 1.0, 0.0);
+        let a = Quaternion::identity();
+        let b = Quaternion::from_axis_angle(axis, PI / 2.0);
+
+        let halfway = a.slerp(b, 0.5);
+
+        assert!(quaternions_equal(
+            &halfway,
+            &Quaternion::from_axis_angle(axis, PI / 4.0)
+        ));
+    }
+
+    #[test]
+    fn test_slerp_takes_the_shorter_arc() {
+        let axis = Tuple4::vector(0.0, 0.0, 1.0);
+        let a = Quaternion::from_axis_angle(axis, 0.1);
+        let b = Quaternion::from_axis_angle(axis, 0.3);
+        let negated_b = Quaternion::new(-b.w, -b.x, -b.y, -b.z);
+
+        let m = a.slerp(negated_b, 0.5).to_matrix();
+
+        assert_approx_eq!(m, Matrix4x4::rotation_z(0.2), EPSILON);
+    }
+
+    #[test]
+    fn test_slerp_between_nearly_parallel_quaternions_degenerates_to_lerp() {
+        let axis = Tuple4::vector(1.0, 1.0, 0.0);
+        let a = Quaternion::from_axis_angle(axis, 1.0);
+        let b = Quaternion::from_axis_angle(axis, 1.0 + 1e-8);
+
+        let q = a.slerp(b, 0.5);
+
+        assert!(q.w.is_finite() && q.x.is_finite() && q.y.is_finite() && q.z.is_finite());
+        assert!((q.magnitude() - 1.0).abs() < EPSILON);
+        assert!(quaternions_equal(
+            &q,
+            &Quaternion::from_axis_angle(axis, 1.0 + 5e-9)
+        ));
+        assert!(quaternions_equal(&a.slerp(a, 0.5), &a));
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{matrix::Matrix4x4, tuple::Tuple4};
+use crate::{
+    bounds::BoundingSphere,
+    matrix::Matrix4x4,
+    tuple::{Elem, Tuple4},
+};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Ray {
     pub origin: Tuple4,
     pub direction: Tuple4,
@@ -7,14 +12,18 @@ pub struct Ray {
 
 impl Ray {
     pub fn new(origin: Tuple4, direction: Tuple4) -> Ray {
+        debug_assert!(origin.is_point(), "ray origin must be a point");
+        debug_assert!(direction.is_vector(), "ray direction must be a vector");
         Ray { origin, direction }
     }
 
-    pub fn position(&self, t: f64) -> Tuple4 {
+    pub fn position(&self, t: Elem) -> Tuple4 {
         self.origin + self.direction * t
     }
 
-    pub fn transform(&self, m: Matrix4x4) -> Ray {
+    /// Carries the ray through `m`. The direction is left unnormalized so
+    /// that `t` values in the new space match those in the old one.
+    pub fn transform(&self, m: &Matrix4x4) -> Ray {
         let new_origin = m * self.origin;
         let new_direction = m * self.direction;
 
@@ -23,6 +32,23 @@ impl Ray {
             direction: new_direction,
         }
     }
+
+    pub fn intersects_sphere_bound(&self, bound: &BoundingSphere) -> bool {
+        let to_center = bound.center - self.origin;
+        let distance_squared = to_center.dot(&to_center);
+        let radius_squared = bound.radius * bound.radius;
+
+        if distance_squared <= radius_squared {
+            return true;
+        }
+
+        let projection = to_center.dot(&self.direction) / self.direction.magnitude();
+        if projection < 0.0 {
+            return false;
+        }
+
+        distance_squared - projection * projection <= radius_squared
+    }
 }
 
 #[cfg(test)]
@@ -39,12 +65,27 @@ mod tests {
         assert_eq!(r.position(2.5), Tuple4::point(4.5, 3.0, 4.0));
     }
 
+    #[test]
+    fn test_ray_intersecting_a_sphere_bound() {
+        let bound = BoundingSphere::new(Tuple4::point(0.0, 0.0, 5.0), 1.0);
+
+        let hit = Ray::new(Tuple4::point(0.0, 0.5, 0.0), Tuple4::vector(0.0, 0.0, 2.0));
+        let miss = Ray::new(Tuple4::point(0.0, 1.5, 0.0), Tuple4::vector(0.0, 0.0, 2.0));
+        let behind = Ray::new(Tuple4::point(0.0, 0.0, 0.0), Tuple4::vector(0.0, 0.0, -1.0));
+        let inside = Ray::new(Tuple4::point(0.0, 0.0, 5.0), Tuple4::vector(0.0, 0.0, -1.0));
+
+        assert!(hit.intersects_sphere_bound(&bound));
+        assert!(!miss.intersects_sphere_bound(&bound));
+        assert!(!behind.intersects_sphere_bound(&bound));
+        assert!(inside.intersects_sphere_bound(&bound));
+    }
+
     #[test]
     fn test_translating_a_ray() {
         let r = Ray::new(Tuple4::point(1.0, 2.0, 3.0), Tuple4::vector(0.0, 1.0, 0.0));
         let m = Matrix4x4::translation(3.0, 4.0, 5.0);
 
-        let r2 = r.transform(m);
+        let r2 = r.transform(&m);
 
         assert_eq!(r2.origin, Tuple4::point(4.0, 6.0, 8.0));
         assert_eq!(r2.direction, Tuple4::vector(0.0, 1.0, 0.0));
@@ -55,9 +96,31 @@ mod tests {
         let r = Ray::new(Tuple4::point(1.0, 2.0, 3.0), Tuple4::vector(0.0, 1.0, 0.0));
         let m = Matrix4x4::scaling(2.0, 3.0, 4.0);
 
-        let r2 = r.transform(m);
+        let r2 = r.transform(&m);
 
         assert_eq!(r2.origin, Tuple4::point(2.0, 6.0, 12.0));
         assert_eq!(r2.direction, Tuple4::vector(0.0, 3.0, 0.0));
     }
+
+    #[test]
+    fn test_transformed_rays_keep_their_t_values() {
+        let r = Ray::new(
+            Tuple4::point(1.0, -2.0, 0.5),
+            Tuple4::vector(0.3, 1.0, -2.0),
+        );
+        let m = Matrix4x4::scaling(2.0, 0.5, 3.0).translate(1.0, 1.0, 0.0);
+
+        let r2 = r.transform(&m);
+
+        for t in [-1.0, 0.0, 0.75, 4.0] {
+            crate::assert_approx_eq!(r2.position(t), m * r.position(t));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ray direction must be a vector")]
+    #[cfg(debug_assertions)]
+    fn test_ray_direction_must_be_a_vector() {
+        Ray::new(Tuple4::point(0.0, 0.0, 0.0), Tuple4::point(0.0, 0.0, 1.0));
+    }
 }
@@ -0,0 +1,128 @@
+use crate::tuple::Elem;
+
+/// Solves `a * x^2 + b * x + c = 0`, returning the real roots in ascending
+/// order.
+///
+/// Uses `q = -(b + sign(b) * sqrt(b^2 - 4ac)) / 2` with roots `q / a` and
+/// `c / q`, which never subtracts two nearly equal numbers, so the small
+/// root stays accurate when `b^2` dwarfs `4ac`. A tangent returns the same
+/// root twice.
+///
+/// However small `a` gets, as long as it isn't zero the same formula still
+/// finds both roots, the second one heading off towards infinity. Only an
+/// exact zero has no quadratic to solve and returns `None`; shapes whose
+/// equation can degenerate to a line handle that case themselves, since
+/// only they know whether it means one hit or none.
+pub fn solve_quadratic(a: Elem, b: Elem, c: Elem) -> Option<(Elem, Elem)> {
+    if a == 0.0 {
+        return None;
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        return Some((0.0, 0.0));
+    }
+
+    let (t1, t2) = (q / a, c / q);
+    Some((t1.min(t2), t1.max(t2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: Elem = 1e-6;
+
+    fn equal(a: Elem, b: Elem) -> bool {
+        (a - b).abs() < EPSILON
+    }
+
+    fn naive(a: Elem, b: Elem, c: Elem) -> (Elem, Elem) {
+        let discriminant = (b * b - 4.0 * a * c).sqrt();
+        (
+            (-b - discriminant) / (2.0 * a),
+            (-b + discriminant) / (2.0 * a),
+        )
+    }
+
+    #[test]
+    fn test_solving_a_quadratic_with_two_roots() {
+        let (t1, t2) = solve_quadratic(1.0, -3.0, 2.0).unwrap();
+
+        assert!(equal(t1, 1.0));
+        assert!(equal(t2, 2.0));
+    }
+
+    #[test]
+    fn test_roots_are_ascending_for_negative_a() {
+        let (t1, t2) = solve_quadratic(-1.0, 3.0, -2.0).unwrap();
+
+        assert!(equal(t1, 1.0));
+        assert!(equal(t2, 2.0));
+    }
+
+    #[test]
+    fn test_a_tangent_returns_the_root_twice() {
+        assert_eq!(solve_quadratic(1.0, -2.0, 1.0), Some((1.0, 1.0)));
+        assert_eq!(solve_quadratic(1.0, 0.0, 0.0), Some((0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_a_negative_discriminant_has_no_roots() {
+        assert_eq!(solve_quadratic(1.0, 0.0, 1.0), None);
+    }
+
+    #[test]
+    fn test_a_zero_quadratic_term_has_no_roots() {
+        assert_eq!(solve_quadratic(0.0, 2.0, -4.0), None);
+        assert_eq!(solve_quadratic(0.0, 0.0, 1.0), None);
+    }
+
+    #[test]
+    fn test_a_tiny_quadratic_term_keeps_both_roots() {
+        let (t1, t2) = solve_quadratic(1e-15, 2.0, -4.0).unwrap();
+
+        assert!((t1 + 2e15).abs() / 2e15 < 1e-6);
+        assert!(equal(t2, 2.0));
+    }
+
+    #[test]
+    fn test_roots_do_not_depend_on_the_scale_of_the_coefficients() {
+        for scale in [1e-12, 1e-6, 1.0, 1e10] {
+            let (t1, t2) = solve_quadratic(scale, -3.0 * scale, 2.0 * scale).unwrap();
+
+            assert!(equal(t1, 1.0), "scale {}: {}", scale, t1);
+            assert!(equal(t2, 2.0), "scale {}: {}", scale, t2);
+        }
+    }
+
+    #[test]
+    fn test_small_root_survives_catastrophic_cancellation() {
+        let (a, b, c) = (1.0, -1e9, 1.0);
+
+        let (small, large) = solve_quadratic(a, b, c).unwrap();
+        let (naive_small, _) = naive(a, b, c);
+
+        assert!((small - 1e-9).abs() / 1e-9 < 1e-12);
+        assert!((large - 1e9).abs() / 1e9 < 1e-12);
+        assert!((naive_small - 1e-9).abs() / 1e-9 > 1e-3);
+    }
+
+    #[test]
+    fn test_positive_root_does_not_go_negative_from_far_away() {
+        // Roots 1e-9 and 1e8; the naive formula rounds the small one to 0.
+        let (a, b, c) = (1.0, -(1e8 + 1e-9), 1e-1);
+
+        let (small, _) = solve_quadratic(a, b, c).unwrap();
+        let (naive_small, _) = naive(a, b, c);
+
+        assert!(small > 0.0);
+        assert!((small - 1e-9).abs() / 1e-9 < 1e-9);
+        assert!(naive_small <= 0.0);
+    }
+}
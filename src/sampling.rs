@@ -0,0 +1,531 @@
+use std::f64::consts::{FRAC_PI_4, PI};
+
+use crate::{
+    canvas::Canvas,
+    tuple::{Elem, Tuple4},
+};
+
+/// Source of 2D sample points in `[0, 1)²`.
+///
+/// A sample is fully determined by the pixel, the sample index within that
+/// pixel and the dimension (one per independent use, e.g. lens vs. light),
+/// so renders are reproducible regardless of evaluation order.
+pub trait Sampler {
+    fn sample_2d(&self, pixel: (usize, usize), index: usize, dimension: usize) -> (f64, f64);
+}
+
+/// Cell centers of a regular `n × n` grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UniformGrid {
+    n: usize,
+}
+
+impl UniformGrid {
+    pub fn new(samples_per_side: usize) -> Self {
+        assert!(samples_per_side > 0, "Grid needs at least one sample");
+        UniformGrid {
+            n: samples_per_side,
+        }
+    }
+}
+
+impl Sampler for UniformGrid {
+    fn sample_2d(&self, _pixel: (usize, usize), index: usize, _dimension: usize) -> (f64, f64) {
+        let (x, y) = stratum(self.n, index);
+        let n = self.n as f64;
+
+        ((x as f64 + 0.5) / n, (y as f64 + 0.5) / n)
+    }
+}
+
+/// One jittered point per cell of an `n × n` grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stratified {
+    seed: u64,
+    n: usize,
+}
+
+impl Stratified {
+    pub fn new(seed: u64, samples_per_side: usize) -> Self {
+        assert!(samples_per_side > 0, "Grid needs at least one sample");
+        Stratified {
+            seed,
+            n: samples_per_side,
+        }
+    }
+}
+
+impl Stratified {
+    pub fn for_frame(seed: u64, frame: u64, samples_per_side: usize) -> Self {
+        Stratified::new(frame_seed(seed, frame), samples_per_side)
+    }
+}
+
+impl Sampler for Stratified {
+    fn sample_2d(&self, pixel: (usize, usize), index: usize, dimension: usize) -> (f64, f64) {
+        let (x, y) = stratum(self.n, index);
+        let (jx, jy) = random_2d(self.seed, pixel, index, dimension);
+        let n = self.n as f64;
+
+        ((x as f64 + jx) / n, (y as f64 + jy) / n)
+    }
+}
+
+/// Halton sequence in bases 2 and 3, decorrelated between pixels and
+/// dimensions by a seeded toroidal shift.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Halton {
+    seed: u64,
+}
+
+impl Halton {
+    pub fn new(seed: u64) -> Self {
+        Halton { seed }
+    }
+
+    pub fn for_frame(seed: u64, frame: u64) -> Self {
+        Halton::new(frame_seed(seed, frame))
+    }
+
+    pub fn point(index: usize) -> (f64, f64) {
+        (radical_inverse(2, index), radical_inverse(3, index))
+    }
+}
+
+impl Sampler for Halton {
+    fn sample_2d(&self, pixel: (usize, usize), index: usize, dimension: usize) -> (f64, f64) {
+        let (u, v) = Halton::point(index + 1);
+        let (du, dv) = random_2d(self.seed, pixel, usize::MAX, dimension);
+
+        ((u + du).fract(), (v + dv).fract())
+    }
+}
+
+/// Derives a per-frame seed so sample patterns decorrelate between frames
+/// of an animation instead of sticking to the screen.
+pub fn frame_seed(seed: u64, frame: u64) -> u64 {
+    mix(mix(seed) ^ frame)
+}
+
+pub fn radical_inverse(base: usize, mut index: usize) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut factor = inv_base;
+    let mut result = 0.0;
+
+    while index > 0 {
+        result += (index % base) as f64 * factor;
+        index /= base;
+        factor *= inv_base;
+    }
+
+    result
+}
+
+/// Maps a unit square sample onto the unit disk, preserving relative area
+/// (Shirley–Chiu concentric mapping).
+pub fn concentric_disk(u: f64, v: f64) -> (f64, f64) {
+    let a = 2.0 * u - 1.0;
+    let b = 2.0 * v - 1.0;
+
+    if a == 0.0 && b == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let (r, theta) = if a.abs() > b.abs() {
+        (a, FRAC_PI_4 * (b / a))
+    } else {
+        (b, 2.0 * FRAC_PI_4 - FRAC_PI_4 * (a / b))
+    };
+
+    (r * theta.cos(), r * theta.sin())
+}
+
+/// Shape of a thin-lens aperture, sampled on `[-1, 1]²` lens coordinates.
+///
+/// `polygon` and `mask` reject degenerate shapes; a hand-built polygon with
+/// fewer than 3 blades or a mask with no texels samples as a `Circle`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aperture {
+    Circle,
+    /// Regular polygon inscribed in the unit circle, first vertex at
+    /// `rotation` radians from +x.
+    Polygon {
+        blades: usize,
+        rotation: f64,
+    },
+    /// Texels of a mask image brighter than a threshold, stretched over
+    /// the lens square with row 0 at the top.
+    Mask {
+        width: usize,
+        height: usize,
+        texels: Vec<(usize, usize)>,
+    },
+}
+
+impl Aperture {
+    pub fn polygon(blades: usize, rotation: f64) -> Self {
+        assert!(blades >= 3, "Aperture polygon needs at least 3 blades");
+        Aperture::Polygon { blades, rotation }
+    }
+
+    pub fn mask(canvas: &Canvas, threshold: f64) -> Self {
+        let (width, height) = (canvas.width(), canvas.height());
+        let texels: Vec<_> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&at| {
+                let c = canvas.get_pixel(at);
+                (c.r + c.g + c.b) / 3.0 > threshold
+            })
+            .collect();
+        assert!(!texels.is_empty(), "Aperture mask has no bright texels");
+
+        Aperture::Mask {
+            width,
+            height,
+            texels,
+        }
+    }
+
+    /// Maps a unit square sample onto the aperture with uniform density.
+    pub fn sample(&self, u: f64, v: f64) -> (f64, f64) {
+        match self {
+            Aperture::Circle => concentric_disk(u, v),
+            Aperture::Polygon { blades, .. } if *blades < 3 => concentric_disk(u, v),
+            Aperture::Mask { texels, .. } if texels.is_empty() => concentric_disk(u, v),
+            Aperture::Polygon { blades, rotation } => {
+                let n = *blades as f64;
+                let k = ((u * n) as usize).min(blades - 1);
+                let u = u * n - k as f64;
+                let a0 = rotation + 2.0 * PI * k as f64 / n;
+                let a1 = rotation + 2.0 * PI * (k + 1) as f64 / n;
+
+                let su = u.sqrt();
+                let b1 = su * (1.0 - v);
+                let b2 = su * v;
+
+                (b1 * a0.cos() + b2 * a1.cos(), b1 * a0.sin() + b2 * a1.sin())
+            }
+            Aperture::Mask {
+                width,
+                height,
+                texels,
+            } => {
+                let n = texels.len() as f64;
+                let k = ((u * n) as usize).min(texels.len() - 1);
+                let ju = u * n - k as f64;
+                let (tx, ty) = texels[k];
+
+                (
+                    (tx as f64 + ju) / *width as f64 * 2.0 - 1.0,
+                    1.0 - (ty as f64 + v) / *height as f64 * 2.0,
+                )
+            }
+        }
+    }
+}
+
+/// Uniformly distributed direction on the hemisphere around +z.
+pub fn uniform_hemisphere(u: f64, v: f64) -> Tuple4 {
+    let z = u;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * v;
+
+    Tuple4::vector((r * phi.cos()) as Elem, (r * phi.sin()) as Elem, z as Elem)
+}
+
+/// Cosine-weighted direction on the hemisphere around +z.
+pub fn cosine_hemisphere(u: f64, v: f64) -> Tuple4 {
+    let (x, y) = concentric_disk(u, v);
+    let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+
+    Tuple4::vector(x as Elem, y as Elem, z as Elem)
+}
+
+fn stratum(n: usize, index: usize) -> (usize, usize) {
+    let i = index % (n * n);
+
+    (i % n, i / n)
+}
+
+fn random_2d(seed: u64, pixel: (usize, usize), index: usize, dimension: usize) -> (f64, f64) {
+    let mut h = mix(seed);
+    for n in [pixel.0, pixel.1, index, dimension] {
+        h = mix(h ^ n as u64);
+    }
+
+    (to_unit(h), to_unit(mix(h)))
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn to_unit(h: u64) -> f64 {
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::DEFAULT_EPSILON;
+
+    const EPSILON: f64 = 1e-6;
+
+    fn equal(a: f64, b: f64) -> bool {
+        (a - b).abs() < EPSILON
+    }
+
+    #[test]
+    fn test_uniform_grid_returns_cell_centers() {
+        let sampler = UniformGrid::new(2);
+
+        let samples: Vec<_> = (0..4).map(|i| sampler.sample_2d((0, 0), i, 0)).collect();
+
+        assert_eq!(
+            samples,
+            vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+        );
+    }
+
+    #[test]
+    fn test_stratified_samples_fall_one_per_stratum() {
+        let n = 4;
+        let sampler = Stratified::new(7, n);
+        let mut hits = vec![0; n * n];
+
+        for i in 0..n * n {
+            let (u, v) = sampler.sample_2d((3, 5), i, 0);
+            let cell = (v * n as f64) as usize * n + (u * n as f64) as usize;
+            hits[cell] += 1;
+        }
+
+        assert!(hits.iter().all(|&h| h == 1));
+    }
+
+    #[test]
+    fn test_stratified_samples_are_deterministic() {
+        let a = Stratified::new(42, 3);
+        let b = Stratified::new(42, 3);
+
+        for i in 0..9 {
+            assert_eq!(a.sample_2d((1, 2), i, 1), b.sample_2d((1, 2), i, 1));
+        }
+    }
+
+    #[test]
+    fn test_stratified_samples_differ_between_pixels_and_dimensions() {
+        let sampler = Stratified::new(42, 1);
+
+        let a = sampler.sample_2d((0, 0), 0, 0);
+        let b = sampler.sample_2d((1, 0), 0, 0);
+        let c = sampler.sample_2d((0, 0), 0, 1);
+
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_frame_seeding_is_deterministic() {
+        assert_eq!(frame_seed(42, 3), frame_seed(42, 3));
+        assert_eq!(
+            Stratified::for_frame(42, 3, 2).sample_2d((1, 1), 0, 0),
+            Stratified::for_frame(42, 3, 2).sample_2d((1, 1), 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_consecutive_frames_use_different_offsets_with_the_same_mean() {
+        let n = 32;
+        let frame0 = Stratified::for_frame(42, 0, n);
+        let frame1 = Stratified::for_frame(42, 1, n);
+
+        let mean = |sampler: &Stratified| {
+            (0..n * n)
+                .map(|i| sampler.sample_2d((5, 5), i, 0))
+                .fold((0.0, 0.0), |(u, v), (a, b)| (u + a, v + b))
+        };
+
+        assert_ne!(
+            frame0.sample_2d((5, 5), 0, 0),
+            frame1.sample_2d((5, 5), 0, 0)
+        );
+        let (u0, v0) = mean(&frame0);
+        let (u1, v1) = mean(&frame1);
+        let count = (n * n) as f64;
+        assert!((u0 / count - 0.5).abs() < 1e-3 && (u1 / count - 0.5).abs() < 1e-3);
+        assert!((v0 / count - 0.5).abs() < 1e-3 && (v1 / count - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_halton_frames_are_decorrelated() {
+        assert_ne!(
+            Halton::for_frame(1, 0).sample_2d((0, 0), 0, 0),
+            Halton::for_frame(1, 1).sample_2d((0, 0), 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_first_halton_points() {
+        let expected = [
+            (0.5, 1.0 / 3.0),
+            (0.25, 2.0 / 3.0),
+            (0.75, 1.0 / 9.0),
+            (0.125, 4.0 / 9.0),
+            (0.625, 7.0 / 9.0),
+        ];
+
+        for (i, (u, v)) in expected.iter().enumerate() {
+            let (a, b) = Halton::point(i + 1);
+            assert!(equal(a, *u));
+            assert!(equal(b, *v));
+        }
+    }
+
+    #[test]
+    fn test_halton_samples_stay_in_unit_square() {
+        let sampler = Halton::new(3);
+
+        for i in 0..256 {
+            let (u, v) = sampler.sample_2d((4, 4), i, 2);
+            assert!((0.0..1.0).contains(&u));
+            assert!((0.0..1.0).contains(&v));
+        }
+    }
+
+    #[test]
+    fn test_concentric_disk_maps_into_unit_disk() {
+        let sampler = Stratified::new(1, 16);
+
+        for i in 0..256 {
+            let (u, v) = sampler.sample_2d((0, 0), i, 0);
+            let (x, y) = concentric_disk(u, v);
+            assert!(x * x + y * y <= 1.0 + EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_concentric_disk_preserves_area() {
+        let n = 64;
+        let sampler = Stratified::new(9, n);
+
+        let inside = (0..n * n)
+            .map(|i| sampler.sample_2d((0, 0), i, 0))
+            .map(|(u, v)| concentric_disk(u, v))
+            .filter(|(x, y)| x * x + y * y < 0.25)
+            .count();
+
+        let fraction = inside as f64 / (n * n) as f64;
+        assert!((fraction - 0.25).abs() < 0.01);
+    }
+
+    fn inside_polygon(blades: usize, rotation: f64, (x, y): (f64, f64)) -> bool {
+        (0..blades).all(|k| {
+            let a0 = rotation + 2.0 * PI * k as f64 / blades as f64;
+            let a1 = rotation + 2.0 * PI * (k + 1) as f64 / blades as f64;
+            let (x0, y0) = (a0.cos(), a0.sin());
+            let (x1, y1) = (a1.cos(), a1.sin());
+            (x1 - x0) * (y - y0) - (y1 - y0) * (x - x0) >= -EPSILON
+        })
+    }
+
+    #[test]
+    fn test_circle_aperture_matches_concentric_disk() {
+        let sampler = Stratified::new(2, 8);
+
+        for i in 0..64 {
+            let (u, v) = sampler.sample_2d((0, 0), i, 0);
+            assert_eq!(Aperture::Circle.sample(u, v), concentric_disk(u, v));
+        }
+    }
+
+    #[test]
+    fn test_degenerate_apertures_sample_as_a_circle() {
+        let sampler = Stratified::new(3, 8);
+        let degenerate = [
+            Aperture::Polygon {
+                blades: 0,
+                rotation: 0.0,
+            },
+            Aperture::Polygon {
+                blades: 2,
+                rotation: 0.5,
+            },
+            Aperture::Mask {
+                width: 0,
+                height: 0,
+                texels: vec![],
+            },
+        ];
+
+        for aperture in degenerate {
+            for i in 0..64 {
+                let (u, v) = sampler.sample_2d((0, 0), i, 0);
+                assert_eq!(aperture.sample(u, v), concentric_disk(u, v));
+            }
+        }
+    }
+
+    #[test]
+    fn test_polygon_aperture_samples_fall_inside_the_polygon() {
+        let sampler = Stratified::new(11, 64);
+
+        for (blades, rotation) in [(3, 0.0), (5, 0.3), (6, PI / 6.0)] {
+            let aperture = Aperture::polygon(blades, rotation);
+            for i in 0..64 * 64 {
+                let (u, v) = sampler.sample_2d((0, 0), i, 0);
+                assert!(inside_polygon(blades, rotation, aperture.sample(u, v)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_polygon_aperture_covers_every_blade() {
+        let aperture = Aperture::polygon(4, 0.0);
+        let sampler = Stratified::new(4, 16);
+        let mut quadrants = [0; 4];
+
+        for i in 0..256 {
+            let (u, v) = sampler.sample_2d((0, 0), i, 0);
+            let (x, y) = aperture.sample(u, v);
+            let angle = y.atan2(x).rem_euclid(2.0 * PI);
+            quadrants[(angle / (PI / 2.0)) as usize % 4] += 1;
+        }
+
+        assert!(quadrants.iter().all(|&q| (q - 64_i32).abs() < 8));
+    }
+
+    #[test]
+    fn test_mask_aperture_samples_only_bright_texels() {
+        let mut canvas = Canvas::new(4, 4);
+        let white = crate::color::Color::new(1.0, 1.0, 1.0);
+        canvas.put_pixel(white, (0, 0));
+        canvas.put_pixel(white, (3, 2));
+        let aperture = Aperture::mask(&canvas, 0.5);
+        let sampler = Stratified::new(6, 16);
+
+        for i in 0..256 {
+            let (u, v) = sampler.sample_2d((0, 0), i, 0);
+            let (x, y) = aperture.sample(u, v);
+            let tx = ((x + 1.0) / 2.0 * 4.0) as usize;
+            let ty = ((1.0 - y) / 2.0 * 4.0) as usize;
+            assert!((tx, ty) == (0, 0) || (tx, ty) == (3, 2));
+        }
+    }
+
+    #[test]
+    fn test_hemisphere_samples_are_unit_vectors_above_the_plane() {
+        let sampler = Stratified::new(5, 8);
+
+        for i in 0..64 {
+            let (u, v) = sampler.sample_2d((0, 0), i, 0);
+            for d in [uniform_hemisphere(u, v), cosine_hemisphere(u, v)] {
+                assert!(d.is_vector());
+                assert!(d.z >= 0.0);
+                assert!((d.magnitude() - 1.0).abs() < DEFAULT_EPSILON);
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use crate::intersection::{Intersection, Intersections};
+use crate::materials::Material;
+use crate::matrix::Matrix4x4;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::tuple::{Elem, Tuple4};
+
+/// How close to parallel, as a ratio, a ray may be to a surface or an axis
+/// before shapes give up on it. Shapes compare it against squared
+/// quantities divided through by the squared lengths they're made of,
+/// including the local ray direction, which object space doesn't
+/// normalize, so the test holds however the shape is scaled.
+#[cfg(not(feature = "f32"))]
+pub(crate) const PARALLEL_EPSILON: Elem = 1e-12;
+#[cfg(feature = "f32")]
+pub(crate) const PARALLEL_EPSILON: Elem = 1e-8;
+
+/// How far off a boundary, in object-space units, a point may fall and
+/// still count as on it; for example a hit on a cap's rim.
+#[cfg(not(feature = "f32"))]
+pub(crate) const SURFACE_EPSILON: Elem = 1e-9;
+#[cfg(feature = "f32")]
+pub(crate) const SURFACE_EPSILON: Elem = 1e-5;
+
+/// A primitive defined in its own object space and placed in the world by
+/// its transform.
+///
+/// Implementors only describe the shape in object space; the provided
+/// [`Shape::intersect`] and [`Shape::normal_at`] do the conversion to and
+/// from world space. The trait is object safe, so scenes can hold
+/// `Box<dyn Shape>`.
+pub trait Shape {
+    fn transform(&self) -> &Transform;
+
+    fn transform_mut(&mut self) -> &mut Transform;
+
+    fn material(&self) -> &Material;
+
+    fn material_mut(&mut self) -> &mut Material;
+
+    /// Intersections with a ray already carried into object space.
+    fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>>;
+
+    /// The object-space normal at an object-space point. It needn't be
+    /// normalized.
+    fn local_normal_at(&self, local_point: Tuple4) -> Tuple4;
+
+    /// Panics if `m` is singular.
+    fn set_transform(&mut self, m: Matrix4x4) {
+        self.transform_mut()
+            .set(m)
+            .expect("Can't inverse singular matrix");
+    }
+
+    fn set_material(&mut self, m: Material) {
+        *self.material_mut() = m;
+    }
+
+    /// Intersects a world-space ray.
+    fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let local_ray = ray.transform(self.transform().inverse());
+        Intersections::new(self.local_intersect(&local_ray))
+    }
+
+    /// The unit world-space normal at a world-space point.
+    fn normal_at(&self, world_point: Tuple4) -> Tuple4 {
+        let local_point = *self.transform().inverse() * world_point;
+        self.transform()
+            .apply_normal(self.local_normal_at(local_point))
+    }
+}
+
+/// Shapes are compared by identity: two references are equal only if they
+/// point at the same shape.
+impl PartialEq for dyn Shape + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::color::Color;
+    use crate::sphere::Sphere;
+    use crate::tuple::consts::{FRAC_1_SQRT_2, PI};
+
+    /// Records the object-space ray it was asked to intersect.
+    #[derive(Default)]
+    struct TestShape {
+        transform: Transform,
+        material: Material,
+        saved_ray: Cell<Option<Ray>>,
+    }
+
+    impl Shape for TestShape {
+        fn transform(&self) -> &Transform {
+            &self.transform
+        }
+
+        fn transform_mut(&mut self) -> &mut Transform {
+            &mut self.transform
+        }
+
+        fn material(&self) -> &Material {
+            &self.material
+        }
+
+        fn material_mut(&mut self) -> &mut Material {
+            &mut self.material
+        }
+
+        fn local_intersect(&self, local_ray: &Ray) -> Vec<Intersection<'_>> {
+            self.saved_ray.set(Some(*local_ray));
+            Vec::new()
+        }
+
+        fn local_normal_at(&self, local_point: Tuple4) -> Tuple4 {
+            Tuple4::vector(local_point.x, local_point.y, local_point.z)
+        }
+    }
+
+    #[test]
+    fn test_default_transformation() {
+        let s = TestShape::default();
+
+        assert_eq!(*s.transform().matrix(), Matrix4x4::identity());
+    }
+
+    #[test]
+    fn test_assigning_a_transformation() {
+        let mut s = TestShape::default();
+
+        s.set_transform(Matrix4x4::translation(2.0, 3.0, 4.0));
+
+        assert_eq!(
+            *s.transform().matrix(),
+            Matrix4x4::translation(2.0, 3.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn test_default_material() {
+        let s = TestShape::default();
+
+        assert_eq!(*s.material(), Material::default());
+    }
+
+    #[test]
+    fn test_assigning_a_material() {
+        let mut s = TestShape::default();
+        let m = Material {
+            ambient: 1.0,
+            ..Default::default()
+        };
+
+        s.set_material(m.clone());
+
+        assert_eq!(*s.material(), m);
+    }
+
+    #[test]
+    fn test_intersecting_a_scaled_shape_with_a_ray() {
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
+        let mut s = TestShape::default();
+        s.set_transform(Matrix4x4::scaling(2.0, 2.0, 2.0));
+
+        s.intersect(&r);
+
+        let saved = s.saved_ray.get().unwrap();
+        assert_eq!(saved.origin, Tuple4::point(0.0, 0.0, -2.5));
+        assert_eq!(saved.direction, Tuple4::vector(0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn test_intersecting_a_translated_shape_with_a_ray() {
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
+        let mut s = TestShape::default();
+        s.set_transform(Matrix4x4::translation(5.0, 0.0, 0.0));
+
+        s.intersect(&r);
+
+        let saved = s.saved_ray.get().unwrap();
+        assert_eq!(saved.origin, Tuple4::point(-5.0, 0.0, -5.0));
+        assert_eq!(saved.direction, Tuple4::vector(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_computing_the_normal_on_a_translated_shape() {
+        let mut s = TestShape::default();
+        s.set_transform(Matrix4x4::translation(0.0, 1.0, 0.0));
+
+        let n = s.normal_at(Tuple4::point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+
+        assert_approx_eq!(n, Tuple4::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn test_computing_the_normal_on_a_transformed_shape() {
+        let mut s = TestShape::default();
+        s.set_transform(Matrix4x4::scaling(1.0, 0.5, 1.0) * Matrix4x4::rotation_z(PI / 5.0));
+
+        let n = s.normal_at(Tuple4::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+
+        assert_approx_eq!(n, Tuple4::vector(0.0, 0.97014, -0.24254), 1e-5);
+    }
+
+    #[test]
+    fn test_shapes_as_trait_objects() {
+        let mut sphere = Sphere::new();
+        sphere.set_material(Material {
+            color: Color::RED,
+            ..Default::default()
+        });
+        let shapes: Vec<Box<dyn Shape>> = vec![Box::new(sphere), Box::new(TestShape::default())];
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
+
+        let xs: Vec<_> = shapes.iter().map(|s| s.intersect(&r)).collect();
+
+        assert_eq!(xs[0].len(), 2);
+        assert!(xs[1].is_empty());
+        assert!(*xs[0][0].object == *shapes[0]);
+        assert!(*xs[0][0].object != *shapes[1]);
+        assert_eq!(xs[0][0].object.material().color, Color::RED);
+    }
+}
@@ -0,0 +1,363 @@
+//! Property checks over random rays and transforms.
+//!
+//! Every shape must report finite, sorted hits that land on its surface and
+//! a hit consistent with them; shapes that enclose a volume must also be
+//! crossed an even number of times from outside.
+//!
+//! Runs `DEFAULT_CASES` cases per shape; set `RAY_TRACER_FUZZ_CASES` to
+//! crank the count up locally.
+
+use crate::tuple::consts::PI;
+
+use crate::{
+    approx::tolerance,
+    cone::Cone,
+    cube::Cube,
+    cylinder::Cylinder,
+    matrix::Matrix4x4,
+    plane::Plane,
+    ray::Ray,
+    shape::Shape,
+    sphere::Sphere,
+    test_rng::Lcg,
+    triangle::Triangle,
+    tuple::{Elem, Tuple4},
+};
+
+const DEFAULT_CASES: usize = 500;
+const EPSILON: Elem = 1e-6;
+
+/// Profiles, as `(radius, y)` corners, of the cylinders and cones under
+/// test: each is cut off at y = ±1 and, when closed, capped.
+const OPEN_CYLINDER: [(Elem, Elem); 2] = [(1.0, -1.0), (1.0, 1.0)];
+const CLOSED_CYLINDER: [(Elem, Elem); 4] = [(0.0, -1.0), (1.0, -1.0), (1.0, 1.0), (0.0, 1.0)];
+const OPEN_CONE: [(Elem, Elem); 3] = [(1.0, -1.0), (0.0, 0.0), (1.0, 1.0)];
+const CLOSED_CONE: [(Elem, Elem); 5] =
+    [(0.0, -1.0), (1.0, -1.0), (0.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+
+/// A shape to fuzz, along with enough of its object-space geometry to
+/// check the hits it reports.
+struct Subject {
+    shape: Box<dyn Shape>,
+    /// How far an object-space point is from the surface.
+    distance: fn(Tuple4) -> Elem,
+    /// For shapes that enclose a volume, rays starting outside must cross
+    /// them an even number of times.
+    solid: Option<Solid>,
+}
+
+struct Solid {
+    /// Whether an object-space point is enclosed.
+    inside: fn(Tuple4) -> bool,
+    /// How far an object-space point is from the nearest edge, where faces
+    /// meet and rounding may count a hit on either face, both or neither.
+    /// Rays that come that close are left out of the parity check.
+    edge_distance: fn(Tuple4) -> Elem,
+    /// The heights of the horizontal planes the edges lie in, so rays
+    /// whose hit near an edge was dropped altogether are left out too.
+    edge_planes: &'static [Elem],
+}
+
+fn cases() -> usize {
+    std::env::var("RAY_TRACER_FUZZ_CASES")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(DEFAULT_CASES)
+}
+
+fn magnitude(rng: &mut Lcg) -> Elem {
+    Elem::powf(10.0, rng.range(-6.0, 3.0))
+}
+
+fn random_transform(rng: &mut Lcg) -> Matrix4x4 {
+    let t = Matrix4x4::translation(
+        rng.range(-10.0, 10.0),
+        rng.range(-10.0, 10.0),
+        rng.range(-10.0, 10.0),
+    );
+    let r = Matrix4x4::rotation_x(rng.range(0.0, 2.0 * PI))
+        * Matrix4x4::rotation_y(rng.range(0.0, 2.0 * PI))
+        * Matrix4x4::rotation_z(rng.range(0.0, 2.0 * PI));
+    let sh = Matrix4x4::shearing(
+        rng.range(-1.0, 1.0),
+        0.0,
+        0.0,
+        rng.range(-1.0, 1.0),
+        0.0,
+        0.0,
+    );
+    let s = Matrix4x4::scaling(
+        rng.range(0.05, 5.0),
+        rng.range(0.05, 5.0),
+        rng.range(0.05, 5.0),
+    );
+
+    t * r * sh * s
+}
+
+fn radius(p: Tuple4) -> Elem {
+    (p.x * p.x + p.z * p.z).sqrt()
+}
+
+/// Distance from `p` to the surface swept out by turning `profile` about
+/// the y axis.
+fn profile_distance(p: Tuple4, profile: &[(Elem, Elem)]) -> Elem {
+    let (r, y) = (radius(p), p.y);
+
+    profile
+        .windows(2)
+        .map(|w| {
+            let ((r0, y0), (r1, y1)) = (w[0], w[1]);
+            let (dr, dy) = (r1 - r0, y1 - y0);
+            let s = (((r - r0) * dr + (y - y0) * dy) / (dr * dr + dy * dy)).clamp(0.0, 1.0);
+            (r - r0 - s * dr).hypot(y - y0 - s * dy)
+        })
+        .fold(Elem::INFINITY, Elem::min)
+}
+
+/// Distance from `p` to the nearest circle swept out by one of the inner
+/// corners of `profile`.
+fn corner_distance(p: Tuple4, profile: &[(Elem, Elem)]) -> Elem {
+    let (r, y) = (radius(p), p.y);
+
+    profile[1..profile.len() - 1]
+        .iter()
+        .map(|&(rc, yc)| (r - rc).hypot(y - yc))
+        .fold(Elem::INFINITY, Elem::min)
+}
+
+/// A ray from somewhere around the scene. Most are aimed at a point near
+/// the shape placed by `transform`, since rays in random directions
+/// seldom come close.
+fn random_ray(rng: &mut Lcg, transform: Matrix4x4) -> Ray {
+    let origin = Tuple4::point(
+        rng.range(-20.0, 20.0),
+        rng.range(-20.0, 20.0),
+        rng.range(-20.0, 20.0),
+    );
+    let scale = if rng.next() < 0.02 {
+        0.0
+    } else {
+        magnitude(rng)
+    };
+    let direction = if rng.next() < 0.75 {
+        let target = transform
+            * Tuple4::point(
+                rng.range(-1.5, 1.5),
+                rng.range(-1.5, 1.5),
+                rng.range(-1.5, 1.5),
+            );
+        (target - origin).normalize()
+    } else {
+        Tuple4::vector(
+            rng.range(-1.0, 1.0),
+            rng.range(-1.0, 1.0),
+            rng.range(-1.0, 1.0),
+        )
+    };
+
+    Ray::new(origin, direction * scale)
+}
+
+fn check_invariants(mut subject: Subject, seed: u64) {
+    let mut rng = Lcg::new(seed);
+
+    for case in 0..cases() {
+        let transform = random_transform(&mut rng);
+        subject.shape.set_transform(transform);
+        let s = &*subject.shape;
+        let r = random_ray(&mut rng, transform);
+
+        let xs = s.intersect(&r);
+        let ts: Vec<_> = (0..xs.len()).map(|i| xs[i].t).collect();
+
+        assert!(ts.iter().all(|t| t.is_finite()), "case {}: {:?}", case, ts);
+        assert!(
+            ts.windows(2).all(|w| w[0] <= w[1]),
+            "case {}: {:?}",
+            case,
+            ts
+        );
+
+        // Hits are found in object space and mapped back; how far off the
+        // surface they can land grows with the coordinates involved.
+        let inverse = *s.transform().inverse();
+        let local_origin = inverse * r.origin;
+        let local_direction = inverse * r.direction;
+        let scale = |t: Elem| {
+            1.0 + (local_origin - Tuple4::point(0.0, 0.0, 0.0)).magnitude()
+                + (t * local_direction).magnitude()
+        };
+
+        if let Some(solid) = &subject.solid {
+            let outside = !(solid.inside)(local_origin)
+                && (subject.distance)(local_origin) > tolerance(EPSILON) * scale(0.0);
+            let crossings = solid
+                .edge_planes
+                .iter()
+                .filter(|_| local_direction.y != 0.0)
+                .map(|y| (y - local_origin.y) / local_direction.y);
+            let on_edge = ts.iter().copied().chain(crossings).any(|t| {
+                (solid.edge_distance)(inverse * r.position(t)) <= tolerance(EPSILON) * scale(t)
+            });
+            if outside && !on_edge {
+                assert_eq!(ts.len() % 2, 0, "case {}: {:?}", case, ts);
+            }
+        }
+
+        let expected_hit = ts.iter().copied().filter(|&t| t >= 0.0).reduce(Elem::min);
+        assert_eq!(xs.hit().map(|h| h.t), expected_hit, "case {}", case);
+
+        for &t in &ts {
+            let p = inverse * r.position(t);
+            let distance = (subject.distance)(p);
+            assert!(
+                distance <= tolerance(EPSILON) * scale(t),
+                "case {}: {:?} is {} off the surface",
+                case,
+                p,
+                distance
+            );
+
+            let n = s.normal_at(r.position(t));
+            assert!(n.w.abs() < EPSILON, "case {}: {:?}", case, n);
+            assert!(
+                (n.magnitude() - 1.0).abs() < EPSILON,
+                "case {}: {:?}",
+                case,
+                n
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sphere_intersection_invariants() {
+    let subject = Subject {
+        shape: Box::new(Sphere::new()),
+        distance: |p| ((p - Tuple4::point(0.0, 0.0, 0.0)).magnitude() - 1.0).abs(),
+        solid: Some(Solid {
+            inside: |p| (p - Tuple4::point(0.0, 0.0, 0.0)).magnitude() < 1.0,
+            edge_distance: |_| Elem::INFINITY,
+            edge_planes: &[],
+        }),
+    };
+
+    check_invariants(subject, 0x5eed);
+}
+
+#[test]
+fn test_plane_intersection_invariants() {
+    let subject = Subject {
+        shape: Box::new(Plane::new()),
+        distance: |p| p.y.abs(),
+        solid: None,
+    };
+
+    check_invariants(subject, 0x91a4e);
+}
+
+#[test]
+fn test_cube_intersection_invariants() {
+    let subject = Subject {
+        shape: Box::new(Cube::new()),
+        distance: |p| (p.x.abs().max(p.y.abs()).max(p.z.abs()) - 1.0).abs(),
+        solid: Some(Solid {
+            inside: |p| p.x.abs() < 1.0 && p.y.abs() < 1.0 && p.z.abs() < 1.0,
+            edge_distance: |p| {
+                let mut a = [p.x.abs(), p.y.abs(), p.z.abs()];
+                a.sort_by(Elem::total_cmp);
+                (1.0 - a[1]).hypot(1.0 - a[2])
+            },
+            edge_planes: &[],
+        }),
+    };
+
+    check_invariants(subject, 0xc0be);
+}
+
+fn cylinder(closed: bool) -> Box<dyn Shape> {
+    let mut shape = Cylinder::new();
+    shape.minimum = -1.0;
+    shape.maximum = 1.0;
+    shape.closed = closed;
+    Box::new(shape)
+}
+
+#[test]
+fn test_cylinder_intersection_invariants() {
+    let subject = Subject {
+        shape: cylinder(false),
+        distance: |p| profile_distance(p, &OPEN_CYLINDER),
+        solid: None,
+    };
+
+    check_invariants(subject, 0xc711);
+}
+
+#[test]
+fn test_closed_cylinder_intersection_invariants() {
+    let subject = Subject {
+        shape: cylinder(true),
+        distance: |p| profile_distance(p, &CLOSED_CYLINDER),
+        solid: Some(Solid {
+            inside: |p| radius(p) < 1.0 && p.y.abs() < 1.0,
+            edge_distance: |p| corner_distance(p, &CLOSED_CYLINDER),
+            edge_planes: &[-1.0, 1.0],
+        }),
+    };
+
+    check_invariants(subject, 0xc712);
+}
+
+fn cone(closed: bool) -> Box<dyn Shape> {
+    let mut shape = Cone::new();
+    shape.minimum = -1.0;
+    shape.maximum = 1.0;
+    shape.closed = closed;
+    Box::new(shape)
+}
+
+#[test]
+fn test_cone_intersection_invariants() {
+    let subject = Subject {
+        shape: cone(false),
+        distance: |p| profile_distance(p, &OPEN_CONE),
+        solid: None,
+    };
+
+    check_invariants(subject, 0xc0e1);
+}
+
+#[test]
+fn test_closed_cone_intersection_invariants() {
+    let subject = Subject {
+        shape: cone(true),
+        distance: |p| profile_distance(p, &CLOSED_CONE),
+        solid: Some(Solid {
+            inside: |p| radius(p) < p.y.abs() && p.y.abs() < 1.0,
+            edge_distance: |p| corner_distance(p, &CLOSED_CONE),
+            edge_planes: &[-1.0, 0.0, 1.0],
+        }),
+    };
+
+    check_invariants(subject, 0xc0e2);
+}
+
+/// The triangle lies in z = 0 with corners (0, 1), (-1, 0) and (1, 0);
+/// its distance is measured to that plane, or to the nearest edge's line
+/// when that's further.
+#[test]
+fn test_triangle_intersection_invariants() {
+    let subject = Subject {
+        shape: Box::new(Triangle::new(
+            Tuple4::point(0.0, 1.0, 0.0),
+            Tuple4::point(-1.0, 0.0, 0.0),
+            Tuple4::point(1.0, 0.0, 0.0),
+        )),
+        distance: |p| p.z.abs().max(-p.y).max(p.y + p.x.abs() - 1.0),
+        solid: None,
+    };
+
+    check_invariants(subject, 0x7e1a);
+}
@@ -0,0 +1,111 @@
+//! SSE2 kernels for the hot `Matrix4x4` products. These are `f64` only, so
+//! the `f32` feature falls back to the scalar code.
+//!
+//! Each output element accumulates its products in the same order as the
+//! scalar code and without fused multiply-adds, so the results are
+//! bit-for-bit identical.
+
+use std::arch::x86_64::{
+    __m128d, _mm_add_pd, _mm_loadu_pd, _mm_mul_pd, _mm_set1_pd, _mm_set_pd, _mm_storeu_pd,
+};
+
+/// Row-major `a * b` for 4x4 matrices.
+pub(crate) fn mul_matrix(a: &[[f64; 4]; 4], b: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut out = [[0.0; 4]; 4];
+
+    // SAFETY: SSE2 is part of the x86_64 baseline, and every load and store
+    // touches two in-bounds elements through unaligned intrinsics.
+    unsafe {
+        for y in 0..4 {
+            let row = |k: usize| -> (__m128d, __m128d) {
+                let s = _mm_set1_pd(a[y][k]);
+                let p = b[k].as_ptr();
+                (
+                    _mm_mul_pd(s, _mm_loadu_pd(p)),
+                    _mm_mul_pd(s, _mm_loadu_pd(p.add(2))),
+                )
+            };
+
+            let (mut lo, mut hi) = row(0);
+            for k in 1..4 {
+                let (l, h) = row(k);
+                lo = _mm_add_pd(lo, l);
+                hi = _mm_add_pd(hi, h);
+            }
+
+            let p = out[y].as_mut_ptr();
+            _mm_storeu_pd(p, lo);
+            _mm_storeu_pd(p.add(2), hi);
+        }
+    }
+
+    out
+}
+
+/// Row-major `m * t` for a 4x4 matrix and a 4-element column.
+pub(crate) fn mul_tuple(m: &[[f64; 4]; 4], t: [f64; 4]) -> [f64; 4] {
+    let mut out = [0.0; 4];
+
+    // SAFETY: SSE2 is part of the x86_64 baseline, and both stores write two
+    // in-bounds elements through unaligned intrinsics.
+    unsafe {
+        let col = |k: usize| -> (__m128d, __m128d) {
+            let s = _mm_set1_pd(t[k]);
+            (
+                _mm_mul_pd(_mm_set_pd(m[1][k], m[0][k]), s),
+                _mm_mul_pd(_mm_set_pd(m[3][k], m[2][k]), s),
+            )
+        };
+
+        let (mut lo, mut hi) = col(0);
+        for k in 1..4 {
+            let (l, h) = col(k);
+            lo = _mm_add_pd(lo, l);
+            hi = _mm_add_pd(hi, h);
+        }
+
+        _mm_storeu_pd(out.as_mut_ptr(), lo);
+        _mm_storeu_pd(out.as_mut_ptr().add(2), hi);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{mul_matrix_scalar, mul_tuple_scalar};
+    use crate::test_rng::Lcg;
+
+    fn array<const N: usize>(rng: &mut Lcg) -> [f64; N] {
+        let scale = 10f64.powf(rng.range(-3.0, 3.0));
+        [0.0; N].map(|_| rng.range(-scale, scale))
+    }
+
+    fn matrix(rng: &mut Lcg) -> [[f64; 4]; 4] {
+        let scale = 10f64.powf(rng.range(-3.0, 3.0));
+        [[0.0; 4]; 4].map(|row| row.map(|_| rng.range(-scale, scale)))
+    }
+
+    #[test]
+    fn test_matrix_product_matches_scalar_path() {
+        let mut rng = Lcg::new(7);
+
+        for _ in 0..500 {
+            let (a, b) = (matrix(&mut rng), matrix(&mut rng));
+
+            assert_eq!(mul_matrix(&a, &b), mul_matrix_scalar(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_tuple_product_matches_scalar_path() {
+        let mut rng = Lcg::new(11);
+
+        for _ in 0..500 {
+            let (m, t) = (matrix(&mut rng), array(&mut rng));
+
+            assert_eq!(mul_tuple(&m, t), mul_tuple_scalar(&m, t));
+        }
+    }
+}
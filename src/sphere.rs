@@ -1,16 +1,18 @@
-use std::ops::Index;
-
+use crate::bounds::BoundingSphere;
+use crate::intersection::Intersection;
 use crate::materials::Material;
-use crate::matrix::Matrix4x4;
 use crate::ray::Ray;
-use crate::tuple::Tuple4;
+use crate::roots::solve_quadratic;
+use crate::shape::Shape;
+use crate::transform::Transform;
+use crate::tuple::{Elem, Tuple4};
 
 #[allow(dead_code)]
 #[derive(PartialEq)]
 pub struct Sphere {
     origin: Tuple4,
-    radius: f64,
-    transform: Matrix4x4,
+    radius: Elem,
+    transform: Transform,
     material: Material,
 }
 
@@ -18,7 +20,7 @@ impl Sphere {
     pub fn new() -> Sphere {
         let origin = Tuple4::point(0.0, 0.0, 0.0);
         let radius = 1.0;
-        let transform = Matrix4x4::identity();
+        let transform = Transform::identity();
         let material = Material::default();
 
         Sphere {
@@ -29,113 +31,64 @@ impl Sphere {
         }
     }
 
-    pub fn intersect(&self, ray: &Ray) -> SphereIntersections {
-        let ray_transformation_matrix = self
-            .transform
-            .inverse()
-            .expect("Can't inverse singular matrix");
-        let transformed_ray = ray.transform(ray_transformation_matrix);
-
-        let sphere_to_ray = transformed_ray.origin - self.origin;
-        let a = transformed_ray.direction.dot(&transformed_ray.direction);
-        let b = 2.0 * transformed_ray.direction.dot(&sphere_to_ray);
-        let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0;
-        let discriminant = b * b - 4.0 * a * c;
-
-        let intersections = if discriminant < 0.0 {
-            Vec::new()
-        } else {
-            let t1 = (-b - discriminant.sqrt()) / (2.0 * a);
-            let i1 = SphereIntersection::new(t1, self);
-            let t2 = (-b + discriminant.sqrt()) / (2.0 * a);
-            let i2 = SphereIntersection::new(t2, self);
-            vec![i1, i2]
-        };
-
-        SphereIntersections::new(intersections)
-    }
-
-    pub fn set_transform(&mut self, m: Matrix4x4) {
-        self.transform = m;
+    pub fn bounds(&self) -> BoundingSphere {
+        BoundingSphere::new(self.origin, self.radius).transform(*self.transform.matrix())
     }
+}
 
-    pub fn normal_at(&self, p: Tuple4) -> Tuple4 {
-        let object_point = self.transform.inverse().unwrap() * p;
-        let object_normal = object_point - Tuple4::point(0.0, 0.0, 0.0);
-        let mut world_normal = self.transform.inverse().unwrap().transpose() * object_normal;
-        world_normal.w = 0.0;
-        world_normal.normalize()
+impl Shape for Sphere {
+    fn transform(&self) -> &Transform {
+        &self.transform
     }
 
-    pub fn set_material(&mut self, m: Material) {
-        self.material = m;
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
     }
 
-    pub fn get_material(&self) -> &Material {
+    fn material(&self) -> &Material {
         &self.material
     }
-}
-
-impl Default for Sphere {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-pub struct SphereIntersection<'a> {
-    pub t: f64,
-    pub sphere: &'a Sphere,
-}
-
-impl SphereIntersection<'_> {
-    pub fn new(t: f64, sphere: &Sphere) -> SphereIntersection {
-        SphereIntersection { t, sphere }
-    }
-}
 
-pub struct SphereIntersections<'a> {
-    intersections: Vec<SphereIntersection<'a>>,
-}
-
-impl SphereIntersections<'_> {
-    pub fn new(intersections: Vec<SphereIntersection<'_>>) -> SphereIntersections {
-        SphereIntersections { intersections }
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
     }
 
-    pub fn len(&self) -> usize {
-        self.intersections.len()
-    }
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let sphere_to_ray = ray.origin - self.origin;
+        let a = ray.direction.dot(&ray.direction);
+        let b = 2.0 * ray.direction.dot(&sphere_to_ray);
+        let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0;
 
-    pub fn is_empty(&self) -> bool {
-        self.intersections.len() == 0
+        match solve_quadratic(a, b, c) {
+            Some((t1, t2)) => vec![Intersection::new(t1, self), Intersection::new(t2, self)],
+            None => Vec::new(),
+        }
     }
 
-    pub fn hit(&self) -> Option<&SphereIntersection> {
-        self.intersections
-            .iter()
-            .filter(|x| x.t >= 0.0)
-            .min_by(|a, b| a.t.partial_cmp(&b.t).expect("Tried to compare to NaN"))
+    fn local_normal_at(&self, p: Tuple4) -> Tuple4 {
+        p - self.origin
     }
 }
 
-impl<'a> Index<usize> for SphereIntersections<'a> {
-    type Output = SphereIntersection<'a>;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.intersections[index]
+impl Default for Sphere {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::f64::consts::PI;
+    use crate::tuple::consts::{FRAC_1_SQRT_2, PI};
     use std::ptr;
 
     use super::*;
+    use crate::matrix::Matrix4x4;
+    use crate::test_rng::Lcg;
+    use crate::{approx::tolerance, assert_approx_eq};
 
-    const EPSILON: f64 = 1e-6;
+    const EPSILON: Elem = 1e-6;
 
-    fn equal(a: f64, b: f64) -> bool {
+    fn equal(a: Elem, b: Elem) -> bool {
         (a - b).abs() < EPSILON
     }
 
@@ -198,94 +151,93 @@ mod tests {
     }
 
     #[test]
-    fn test_intersects_sets_object_on_the_intersection() {
-        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
+    fn test_a_ray_without_direction_misses_a_sphere() {
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 0.0));
         let s = Sphere::new();
 
         let xs = s.intersect(&r);
 
-        assert_eq!(xs.len(), 2);
-        assert!(ptr::eq(xs[0].sphere, &s));
+        assert!(xs.is_empty());
     }
 
     #[test]
-    fn test_the_hit_when_all_intersections_have_positive_t() {
+    fn test_intersects_sets_object_on_the_intersection() {
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
-        let i1 = SphereIntersection::new(1.0, &s);
-        let i2 = SphereIntersection::new(2.0, &s);
-        let xs = SphereIntersections::new(vec![i1, i2]);
 
-        let i = xs.hit().unwrap();
+        let xs = s.intersect(&r);
 
-        assert!(ptr::eq(i, &xs.intersections[0]))
+        assert_eq!(xs.len(), 2);
+        assert!(ptr::addr_eq(xs[0].object, &s));
     }
 
     #[test]
-    fn test_the_hit_when_some_intersections_have_negative_t() {
+    fn test_spheres_default_transformation_is_identity() {
         let s = Sphere::new();
-        let i1 = SphereIntersection::new(-1.0, &s);
-        let i2 = SphereIntersection::new(1.0, &s);
-        let xs = SphereIntersections::new(vec![i1, i2]);
-
-        let i = xs.hit().unwrap();
 
-        assert!(ptr::eq(i, &xs.intersections[1]))
+        assert_eq!(*s.transform.matrix(), Matrix4x4::identity());
     }
 
     #[test]
-    fn test_the_hit_when_all_intersections_have_negative_t() {
-        let s = Sphere::new();
-        let i1 = SphereIntersection::new(-2.0, &s);
-        let i2 = SphereIntersection::new(-1.0, &s);
-        let xs = SphereIntersections::new(vec![i1, i2]);
+    fn test_changing_spheres_transformation() {
+        let mut s = Sphere::new();
+        let t = Matrix4x4::translation(2.0, 3.0, 4.0);
 
-        let i = xs.hit();
+        s.set_transform(t);
 
-        assert!(i.is_none());
+        assert_eq!(*s.transform.matrix(), t);
     }
 
     #[test]
-    fn test_the_hit_is_always_the_lowest_nonnegative_intersection() {
-        let s = Sphere::new();
-        let i1 = SphereIntersection::new(5.0, &s);
-        let i2 = SphereIntersection::new(7.0, &s);
-        let i3 = SphereIntersection::new(-3.0, &s);
-        let i4 = SphereIntersection::new(2.0, &s);
-        let xs = SphereIntersections::new(vec![i1, i2, i3, i4]);
-
-        let i = xs.hit().unwrap();
+    #[should_panic(expected = "Can't inverse singular matrix")]
+    fn test_singular_transformation_panics() {
+        let mut s = Sphere::new();
 
-        assert!(ptr::eq(i, &xs.intersections[3]));
+        s.set_transform(Matrix4x4::scaling(1.0, 0.0, 1.0));
     }
 
     #[test]
-    fn test_spheres_default_transformation_is_identity() {
-        let s = Sphere::new();
+    fn test_intersecting_a_scaled_sphere_with_a_ray() {
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
+        let mut s = Sphere::new();
+        s.set_transform(Matrix4x4::scaling(2.0, 2.0, 2.0));
 
-        assert_eq!(s.transform, Matrix4x4::identity());
+        let xs = s.intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 3.0);
+        assert_eq!(xs[1].t, 7.0);
     }
 
     #[test]
-    fn test_changing_spheres_transformation() {
+    fn test_intersecting_a_huge_sphere() {
+        let r = Ray::new(Tuple4::point(0.0, 0.0, -5e6), Tuple4::vector(0.0, 0.0, 1.0));
         let mut s = Sphere::new();
-        let t = Matrix4x4::translation(2.0, 3.0, 4.0);
+        s.set_transform(Matrix4x4::scaling(2e6, 2e6, 2e6));
 
-        s.set_transform(t);
+        let xs = s.intersect(&r);
 
-        assert_eq!(s.transform, t);
+        assert_eq!(xs.len(), 2);
+        assert!((xs[0].t - 3e6).abs() / 3e6 < tolerance(1e-9), "{}", xs[0].t);
+        assert!((xs[1].t - 7e6).abs() / 7e6 < tolerance(1e-9), "{}", xs[1].t);
     }
 
     #[test]
-    fn test_intersecting_a_scaled_sphere_with_a_ray() {
-        let r = Ray::new(Tuple4::point(0.0, 0.0, -5.0), Tuple4::vector(0.0, 0.0, 1.0));
-        let mut s = Sphere::new();
-        s.set_transform(Matrix4x4::scaling(2.0, 2.0, 2.0));
+    fn test_intersecting_a_sphere_with_a_short_direction() {
+        let r = Ray::new(
+            Tuple4::point(0.0, 0.0, -5.0),
+            Tuple4::vector(0.0, 0.0, 1e-7),
+        );
+        let s = Sphere::new();
 
         let xs = s.intersect(&r);
 
         assert_eq!(xs.len(), 2);
-        assert_eq!(xs[0].t, 3.0);
-        assert_eq!(xs[1].t, 7.0);
+        for x in xs.iter() {
+            let p = r.position(x.t) - Tuple4::point(0.0, 0.0, 0.0);
+            assert!((p.magnitude() - 1.0).abs() < tolerance(1e-9), "{:?}", p);
+        }
+        assert!(r.position(xs[0].t).z < 0.0);
     }
 
     #[test]
@@ -330,18 +282,19 @@ mod tests {
         let s = Sphere::new();
 
         let n = s.normal_at(Tuple4::point(
-            3.0_f64.sqrt() / 3.0,
-            3.0_f64.sqrt() / 3.0,
-            3.0_f64.sqrt() / 3.0,
+            Elem::sqrt(3.0) / 3.0,
+            Elem::sqrt(3.0) / 3.0,
+            Elem::sqrt(3.0) / 3.0,
         ));
 
-        assert_eq!(
+        assert_approx_eq!(
             n,
             Tuple4::vector(
-                3.0_f64.sqrt() / 3.0,
-                3.0_f64.sqrt() / 3.0,
-                3.0_f64.sqrt() / 3.0,
-            )
+                Elem::sqrt(3.0) / 3.0,
+                Elem::sqrt(3.0) / 3.0,
+                Elem::sqrt(3.0) / 3.0,
+            ),
+            tolerance(0.0)
         );
     }
 
@@ -350,17 +303,17 @@ mod tests {
         let s = Sphere::new();
 
         let n = s.normal_at(Tuple4::point(
-            3.0_f64.sqrt() / 3.0,
-            3.0_f64.sqrt() / 3.0,
-            3.0_f64.sqrt() / 3.0,
+            Elem::sqrt(3.0) / 3.0,
+            Elem::sqrt(3.0) / 3.0,
+            Elem::sqrt(3.0) / 3.0,
         ));
 
         assert_eq!(
             n,
             Tuple4::vector(
-                3.0_f64.sqrt() / 3.0,
-                3.0_f64.sqrt() / 3.0,
-                3.0_f64.sqrt() / 3.0,
+                Elem::sqrt(3.0) / 3.0,
+                Elem::sqrt(3.0) / 3.0,
+                Elem::sqrt(3.0) / 3.0,
             )
             .normalize()
         );
@@ -371,11 +324,11 @@ mod tests {
         let mut s = Sphere::new();
         s.set_transform(Matrix4x4::translation(0.0, 1.0, 0.0));
 
-        let n = s.normal_at(Tuple4::point(0.0, 1.70711, -0.70711));
+        let n = s.normal_at(Tuple4::point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
 
         assert_eq!(n.x, 0.0);
-        assert!(equal(n.y, 0.707106));
-        assert!(equal(n.z, -0.707106));
+        assert!(equal(n.y, FRAC_1_SQRT_2));
+        assert!(equal(n.z, -FRAC_1_SQRT_2));
         assert!(n.is_vector());
     }
 
@@ -387,16 +340,39 @@ mod tests {
 
         let n = s.normal_at(Tuple4::point(
             0.0,
-            2.0_f64.sqrt() / 2.0,
-            -(2.0_f64.sqrt()) / 2.0,
+            Elem::sqrt(2.0) / 2.0,
+            -(Elem::sqrt(2.0)) / 2.0,
         ));
 
-        assert_eq!(n.x, 0.0);
+        assert!(equal(n.x, 0.0));
         assert!(equal(n.y, 0.970142));
         assert!(equal(n.z, -0.242535));
         assert!(n.is_vector());
     }
 
+    #[test]
+    fn test_sphere_bound_never_culls_a_ray_that_hits() {
+        let mut rng = Lcg::new(29);
+        let mut random = |min, max| rng.range(min, max);
+
+        for _ in 0..1000 {
+            let mut s = Sphere::new();
+            s.set_transform(
+                Matrix4x4::translation(random(-2.0, 2.0), random(-2.0, 2.0), 0.0)
+                    * Matrix4x4::rotation_z(random(0.0, PI))
+                    * Matrix4x4::shearing(random(-1.0, 1.0), 0.0, 0.0, 0.0, 0.0, 0.0)
+                    * Matrix4x4::scaling(random(0.2, 2.0), random(0.2, 2.0), random(0.2, 2.0)),
+            );
+            let origin = Tuple4::point(random(-3.0, 3.0), random(-3.0, 3.0), -6.0);
+            let target = Tuple4::point(random(-3.0, 3.0), random(-3.0, 3.0), 0.0);
+            let r = Ray::new(origin, target - origin);
+
+            if s.intersect(&r).hit().is_some() {
+                assert!(r.intersects_sphere_bound(&s.bounds()));
+            }
+        }
+    }
+
     #[test]
     fn test_sphere_has_default_material() {
         let s = Sphere::new();
@@ -407,8 +383,10 @@ mod tests {
     #[test]
     fn test_sphere_may_be_assigned_a_material() {
         let mut s = Sphere::new();
-        let mut m = Material::default();
-        m.ambient = 1.0;
+        let m = Material {
+            ambient: 1.0,
+            ..Default::default()
+        };
 
         s.set_material(m.clone());
 
@@ -0,0 +1,32 @@
+//! A deterministic generator for randomized tests.
+
+use crate::tuple::Elem;
+
+/// A 64-bit linear congruential generator with Knuth's MMIX constants.
+/// Nowhere near good enough for sampling, but cheap, seedable and the same
+/// on every platform, which is what test inputs need.
+pub(crate) struct Lcg(u64);
+
+impl Lcg {
+    pub(crate) fn new(seed: u64) -> Lcg {
+        Lcg(seed)
+    }
+
+    /// Uniform in `[0, 1)`, from the top 53 bits of the state.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// [`Lcg::next_f64`] as the element type.
+    pub(crate) fn next(&mut self) -> Elem {
+        self.next_f64() as Elem
+    }
+
+    pub(crate) fn range(&mut self, min: Elem, max: Elem) -> Elem {
+        min + (max - min) * self.next()
+    }
+}
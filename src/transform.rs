@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::matrix::Matrix4x4;
+use crate::tuple::Tuple4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingularMatrixError;
+
+impl fmt::Display for SingularMatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "transform matrix is not invertible")
+    }
+}
+
+impl Error for SingularMatrixError {}
+
+/// An invertible `Matrix4x4` stored with its inverse and inverse transpose,
+/// which are computed once on construction or `set` instead of per ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    matrix: Matrix4x4,
+    inverse: Matrix4x4,
+    inverse_transpose: Matrix4x4,
+}
+
+impl Transform {
+    pub fn new(matrix: Matrix4x4) -> Result<Self, SingularMatrixError> {
+        let inverse = matrix.inverse().ok_or(SingularMatrixError)?;
+
+        Ok(Transform {
+            matrix,
+            inverse,
+            inverse_transpose: inverse.transpose(),
+        })
+    }
+
+    pub fn identity() -> Self {
+        Transform {
+            matrix: Matrix4x4::identity(),
+            inverse: Matrix4x4::identity(),
+            inverse_transpose: Matrix4x4::identity(),
+        }
+    }
+
+    /// Replaces the matrix, leaving `self` untouched if it is singular.
+    pub fn set(&mut self, matrix: Matrix4x4) -> Result<(), SingularMatrixError> {
+        *self = Transform::new(matrix)?;
+        Ok(())
+    }
+
+    pub fn matrix(&self) -> &Matrix4x4 {
+        &self.matrix
+    }
+
+    pub fn inverse(&self) -> &Matrix4x4 {
+        &self.inverse
+    }
+
+    pub fn inverse_transpose(&self) -> &Matrix4x4 {
+        &self.inverse_transpose
+    }
+
+    pub fn apply_point(&self, p: Tuple4) -> Tuple4 {
+        self.matrix * p
+    }
+
+    pub fn apply_vector(&self, v: Tuple4) -> Tuple4 {
+        self.matrix * v
+    }
+
+    /// Carries a surface normal through the transform with the inverse
+    /// transpose, which keeps it perpendicular under non-uniform scaling.
+    /// The result is normalized.
+    pub fn apply_normal(&self, n: Tuple4) -> Tuple4 {
+        let mut normal = self.inverse_transpose * n;
+        normal.w = 0.0;
+        normal.normalize()
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl TryFrom<Matrix4x4> for Transform {
+    type Error = SingularMatrixError;
+
+    fn try_from(matrix: Matrix4x4) -> Result<Self, Self::Error> {
+        Transform::new(matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::DEFAULT_EPSILON;
+    use crate::assert_approx_eq;
+    use crate::tuple::{consts::PI, Elem};
+
+    #[test]
+    fn test_caches_inverse_and_inverse_transpose() {
+        let m = Matrix4x4::translation(1.0, 2.0, 3.0)
+            .rotate_y(0.4)
+            .scale(2.0, 1.0, 0.5);
+
+        let t = Transform::new(m).unwrap();
+
+        assert_eq!(*t.matrix(), m);
+        assert_eq!(*t.inverse(), m.inverse().unwrap());
+        assert_eq!(*t.inverse_transpose(), m.inverse().unwrap().transpose());
+    }
+
+    #[test]
+    fn test_singular_matrix_is_an_error() {
+        let m = Matrix4x4::scaling(1.0, 0.0, 1.0);
+
+        assert_eq!(Transform::new(m), Err(SingularMatrixError));
+        assert_eq!(Transform::try_from(m), Err(SingularMatrixError));
+        assert_eq!(
+            SingularMatrixError.to_string(),
+            "transform matrix is not invertible"
+        );
+    }
+
+    #[test]
+    fn test_setting_refreshes_the_cache() {
+        let mut t = Transform::default();
+        let m = Matrix4x4::scaling(2.0, 4.0, 8.0);
+
+        t.set(m).unwrap();
+
+        assert_eq!(*t.inverse(), Matrix4x4::scaling(0.5, 0.25, 0.125));
+    }
+
+    #[test]
+    fn test_setting_a_singular_matrix_keeps_the_old_one() {
+        let mut t = Transform::try_from(Matrix4x4::translation(1.0, 0.0, 0.0)).unwrap();
+
+        assert!(t.set(Matrix4x4::zero()).is_err());
+        assert_eq!(*t.matrix(), Matrix4x4::translation(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_applying_to_points_and_vectors() {
+        let t = Transform::new(Matrix4x4::translation(5.0, -3.0, 2.0)).unwrap();
+
+        assert_eq!(
+            t.apply_point(Tuple4::point(-3.0, 4.0, 5.0)),
+            Tuple4::point(2.0, 1.0, 7.0)
+        );
+        assert_eq!(
+            t.apply_vector(Tuple4::vector(-3.0, 4.0, 5.0)),
+            Tuple4::vector(-3.0, 4.0, 5.0)
+        );
+    }
+
+    #[test]
+    fn test_applying_to_a_normal() {
+        let t = Transform::new(Matrix4x4::scaling(1.0, 2.0, 1.0).translate(3.0, 0.0, 0.0)).unwrap();
+
+        let normal = t.apply_normal(Tuple4::vector(0.0, 1.0, 1.0));
+
+        assert_approx_eq!(normal, Tuple4::vector(0.0, 1.0, 2.0).normalize());
+    }
+
+    #[test]
+    fn test_normal_of_a_squashed_and_rotated_sphere() {
+        let m = Matrix4x4::scaling(1.0, 0.5, 1.0) * Matrix4x4::rotation_z(PI / 5.0);
+        let t = Transform::new(m).unwrap();
+        let p = Tuple4::point(0.0, Elem::sqrt(2.0) / 2.0, -Elem::sqrt(2.0) / 2.0);
+
+        let object_normal = *t.inverse() * p - Tuple4::point(0.0, 0.0, 0.0);
+        let normal = t.apply_normal(object_normal);
+
+        assert_approx_eq!(normal, Tuple4::vector(0.0, 0.97014, -0.24254), 1e-5);
+    }
+
+    #[test]
+    fn test_normals_under_rotation_just_rotate() {
+        let t = Transform::new(Matrix4x4::rotation_x(0.3).rotate_y(-1.2)).unwrap();
+        let n = Tuple4::vector(1.0, -2.0, 0.5).normalize();
+
+        assert_approx_eq!(t.apply_normal(n), t.apply_vector(n));
+    }
+
+    #[test]
+    fn test_normals_stay_perpendicular_to_surfaces() {
+        let t = Transform::new(Matrix4x4::scaling(1.0, 0.5, 3.0).rotate_z(PI / 5.0)).unwrap();
+        let n = Tuple4::vector(1.0, 2.0, -1.0);
+        let tangent = Tuple4::vector(1.0, 0.0, 1.0);
+
+        let normal = t.apply_normal(n);
+
+        assert!(normal.is_vector());
+        assert!(t.apply_vector(tangent).dot(&normal).abs() < DEFAULT_EPSILON);
+    }
+}
@@ -0,0 +1,383 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::matrix::Matrix4x4;
+use crate::tuple::{consts, Elem};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformParseError {
+    UnexpectedToken {
+        token: String,
+        at: usize,
+    },
+    UnexpectedEnd,
+    UnknownTransform {
+        name: String,
+        at: usize,
+    },
+    InvalidNumber {
+        token: String,
+        at: usize,
+    },
+    WrongArgumentCount {
+        name: String,
+        expected: &'static str,
+        found: usize,
+        at: usize,
+    },
+}
+
+impl fmt::Display for TransformParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformParseError::UnexpectedToken { token, at } => {
+                write!(f, "unexpected `{}` at offset {}", token, at)
+            }
+            TransformParseError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            TransformParseError::UnknownTransform { name, at } => {
+                write!(f, "unknown transform `{}` at offset {}", name, at)
+            }
+            TransformParseError::InvalidNumber { token, at } => {
+                write!(f, "invalid number `{}` at offset {}", token, at)
+            }
+            TransformParseError::WrongArgumentCount {
+                name,
+                expected,
+                found,
+                at,
+            } => write!(
+                f,
+                "`{}` at offset {} takes {} arguments, found {}",
+                name, at, expected, found
+            ),
+        }
+    }
+}
+
+impl Error for TransformParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Number(&'a str),
+    Symbol(char),
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Lexer { source, pos: 0 }
+    }
+
+    fn next_token(&mut self) -> Option<(Token<'a>, usize)> {
+        let rest = &self.source[self.pos..];
+        let skipped = rest.len() - rest.trim_start().len();
+        self.pos += skipped;
+
+        let start = self.pos;
+        let c = self.source[start..].chars().next()?;
+        let len = if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+            self.scan(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '+')
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            self.scan(|c| c.is_ascii_alphanumeric() || c == '_')
+        } else {
+            c.len_utf8()
+        };
+        self.pos += len;
+
+        let text = &self.source[start..self.pos];
+        let token = if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+            Token::Number(text)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            Token::Ident(text)
+        } else {
+            Token::Symbol(c)
+        };
+
+        Some((token, start))
+    }
+
+    fn scan(&self, accept: impl Fn(char) -> bool) -> usize {
+        let rest = &self.source[self.pos..];
+        let mut chars = rest.char_indices();
+        chars.next();
+        chars
+            .find(|&(_, c)| !accept(c))
+            .map_or(rest.len(), |(i, _)| i)
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token<'a>, usize)>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<(Token<'a>, usize)> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), TransformParseError> {
+        match self.next() {
+            Some((Token::Symbol(c), _)) if c == symbol => Ok(()),
+            Some((t, at)) => Err(unexpected(&t, at)),
+            None => Err(TransformParseError::UnexpectedEnd),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Matrix4x4, TransformParseError> {
+        let mut m = self.term()?;
+        while let Some((token, at)) = self.next() {
+            match token {
+                Token::Symbol('*') => m = self.term()? * m,
+                t => return Err(unexpected(&t, at)),
+            }
+        }
+
+        Ok(m)
+    }
+
+    fn term(&mut self) -> Result<Matrix4x4, TransformParseError> {
+        let (name, at) = match self.next() {
+            Some((Token::Ident(name), at)) => (name, at),
+            Some((t, at)) => return Err(unexpected(&t, at)),
+            None => return Err(TransformParseError::UnexpectedEnd),
+        };
+        let args = self.arguments()?;
+        let count = |expected: &'static str| TransformParseError::WrongArgumentCount {
+            name: name.to_string(),
+            expected,
+            found: args.len(),
+            at,
+        };
+
+        match name {
+            "translate" => match args[..] {
+                [x, y, z] => Ok(Matrix4x4::translation(x, y, z)),
+                _ => Err(count("3")),
+            },
+            "scale" => match args[..] {
+                [s] => Ok(Matrix4x4::scaling(s, s, s)),
+                [x, y, z] => Ok(Matrix4x4::scaling(x, y, z)),
+                _ => Err(count("1 or 3")),
+            },
+            "rotate_x" | "rotate_y" | "rotate_z" => match args[..] {
+                [r] if name == "rotate_x" => Ok(Matrix4x4::rotation_x(r)),
+                [r] if name == "rotate_y" => Ok(Matrix4x4::rotation_y(r)),
+                [r] => Ok(Matrix4x4::rotation_z(r)),
+                _ => Err(count("1")),
+            },
+            "shear" => match args[..] {
+                [xy, xz, yx, yz, zx, zy] => Ok(Matrix4x4::shearing(xy, xz, yx, yz, zx, zy)),
+                _ => Err(count("6")),
+            },
+            "matrix" => match <[Elem; 16]>::try_from(&args[..]) {
+                Ok(data) => Ok(Matrix4x4::new(data)),
+                Err(_) => Err(count("16")),
+            },
+            _ => Err(TransformParseError::UnknownTransform {
+                name: name.to_string(),
+                at,
+            }),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Elem>, TransformParseError> {
+        self.expect_symbol('(')?;
+        let bracketed = self.peek() == Some(&Token::Symbol('['));
+        if bracketed {
+            self.next();
+        }
+
+        let mut args = Vec::new();
+        loop {
+            match self.next() {
+                Some((Token::Number(text), at)) => args.push(number(text, at)?),
+                Some((t, at)) => return Err(unexpected(&t, at)),
+                None => return Err(TransformParseError::UnexpectedEnd),
+            }
+            match self.peek() {
+                Some(Token::Symbol(',')) => {
+                    self.next();
+                }
+                _ => break,
+            }
+        }
+
+        if bracketed {
+            self.expect_symbol(']')?;
+        }
+        self.expect_symbol(')')?;
+
+        Ok(args)
+    }
+}
+
+fn unexpected(token: &Token<'_>, at: usize) -> TransformParseError {
+    let token = match token {
+        Token::Ident(s) | Token::Number(s) => s.to_string(),
+        Token::Symbol(c) => c.to_string(),
+    };
+
+    TransformParseError::UnexpectedToken { token, at }
+}
+
+fn number(text: &str, at: usize) -> Result<Elem, TransformParseError> {
+    let (digits, factor) = if let Some(digits) = text.strip_suffix("deg") {
+        (digits, consts::PI / 180.0)
+    } else if let Some(digits) = text.strip_suffix("rad") {
+        (digits, 1.0)
+    } else {
+        (text, 1.0)
+    };
+
+    digits
+        .parse::<Elem>()
+        .map(|n| n * factor)
+        .map_err(|_| TransformParseError::InvalidNumber {
+            token: text.to_string(),
+            at,
+        })
+}
+
+impl Matrix4x4 {
+    /// Parses expressions such as `translate(0, 1, 0) * rotate_y(45deg) * scale(2)`.
+    ///
+    /// Terms are applied in reading order, so the example translates first
+    /// and scales last. Supported terms are `translate(x, y, z)`,
+    /// `scale(s)`/`scale(x, y, z)`, `rotate_x|y|z(angle)` with an optional
+    /// `deg` or `rad` suffix (radians otherwise), `shear(xy, xz, yx, yz, zx, zy)`
+    /// and `matrix([16 numbers])` in row-major order.
+    pub fn parse_transform(source: &str) -> Result<Matrix4x4, TransformParseError> {
+        let mut lexer = Lexer::new(source);
+        let mut tokens = Vec::new();
+        while let Some(token) = lexer.next_token() {
+            tokens.push(token);
+        }
+
+        Parser { tokens, pos: 0 }.expression()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tuple::consts::PI;
+
+    use super::*;
+    use crate::assert_approx_eq;
+
+    const EPSILON: Elem = 1e-9;
+
+    #[test]
+    fn test_parsing_a_chained_expression() {
+        let m =
+            Matrix4x4::parse_transform("translate(0,1,0) * rotate_y(45deg) * scale(2)").unwrap();
+
+        let expected = Matrix4x4::scaling(2.0, 2.0, 2.0)
+            * Matrix4x4::rotation_y(PI / 4.0)
+            * Matrix4x4::translation(0.0, 1.0, 0.0);
+        assert_approx_eq!(m, expected, EPSILON);
+    }
+
+    #[test]
+    fn test_parsing_degree_and_radian_angles() {
+        let deg = Matrix4x4::parse_transform("rotate_x(90deg)").unwrap();
+        let rad = Matrix4x4::parse_transform("rotate_x(1.5707963267948966rad)").unwrap();
+        let bare = Matrix4x4::parse_transform("rotate_x(1.5707963267948966)").unwrap();
+
+        let expected = Matrix4x4::rotation_x(PI / 2.0);
+        assert_approx_eq!(deg, expected, EPSILON);
+        assert_approx_eq!(rad, expected, EPSILON);
+        assert_approx_eq!(bare, expected, EPSILON);
+    }
+
+    #[test]
+    fn test_parsing_uniform_and_per_axis_scale() {
+        let uniform = Matrix4x4::parse_transform("scale(3)").unwrap();
+        let axes = Matrix4x4::parse_transform(" scale( 1 , -2.5 , 1e1 ) ").unwrap();
+
+        assert_eq!(uniform, Matrix4x4::scaling(3.0, 3.0, 3.0));
+        assert_eq!(axes, Matrix4x4::scaling(1.0, -2.5, 10.0));
+    }
+
+    #[test]
+    fn test_parsing_shear_and_raw_matrix() {
+        let shear = Matrix4x4::parse_transform("shear(1, 0, 0, 0, 0, 1)").unwrap();
+        let raw =
+            Matrix4x4::parse_transform("matrix([1, 0, 0, 5, 0, 1, 0, 6, 0, 0, 1, 7, 0, 0, 0, 1])")
+                .unwrap();
+
+        assert_eq!(shear, Matrix4x4::shearing(1.0, 0.0, 0.0, 0.0, 0.0, 1.0));
+        assert_eq!(raw, Matrix4x4::translation(5.0, 6.0, 7.0));
+    }
+
+    #[test]
+    fn test_parsing_an_unknown_transform() {
+        let err = Matrix4x4::parse_transform("scale(2) * rotat_y(1)").unwrap_err();
+
+        assert_eq!(
+            err,
+            TransformParseError::UnknownTransform {
+                name: "rotat_y".to_string(),
+                at: 11
+            }
+        );
+    }
+
+    #[test]
+    fn test_parsing_wrong_argument_count() {
+        let err = Matrix4x4::parse_transform("translate(1, 2)").unwrap_err();
+
+        assert_eq!(
+            err,
+            TransformParseError::WrongArgumentCount {
+                name: "translate".to_string(),
+                expected: "3",
+                found: 2,
+                at: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_parsing_an_invalid_number() {
+        let err = Matrix4x4::parse_transform("rotate_z(45dge)").unwrap_err();
+
+        assert_eq!(
+            err,
+            TransformParseError::InvalidNumber {
+                token: "45dge".to_string(),
+                at: 9
+            }
+        );
+    }
+
+    #[test]
+    fn test_parsing_a_missing_operator_and_truncated_input() {
+        let missing = Matrix4x4::parse_transform("scale(2) scale(3)").unwrap_err();
+        let truncated = Matrix4x4::parse_transform("scale(2) * translate(1, 2,").unwrap_err();
+
+        assert_eq!(
+            missing,
+            TransformParseError::UnexpectedToken {
+                token: "scale".to_string(),
+                at: 9
+            }
+        );
+        assert_eq!(truncated, TransformParseError::UnexpectedEnd);
+        assert_eq!(
+            missing.to_string(),
+            "unexpected `scale` at offset 9".to_string()
+        );
+    }
+}
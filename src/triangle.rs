@@ -0,0 +1,220 @@
+use crate::intersection::Intersection;
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::shape::{Shape, PARALLEL_EPSILON};
+use crate::transform::Transform;
+use crate::tuple::Tuple4;
+
+/// A flat triangle through three points. The edges from `p1` and the face
+/// normal are worked out once, up front, since every intersection needs
+/// them.
+#[derive(PartialEq)]
+pub struct Triangle {
+    p1: Tuple4,
+    p2: Tuple4,
+    p3: Tuple4,
+    e1: Tuple4,
+    e2: Tuple4,
+    normal: Tuple4,
+    transform: Transform,
+    material: Material,
+}
+
+impl Triangle {
+    pub fn new(p1: Tuple4, p2: Tuple4, p3: Tuple4) -> Triangle {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Triangle {
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(e1).normalize(),
+            transform: Transform::identity(),
+            material: Material::default(),
+        }
+    }
+
+    pub fn p1(&self) -> Tuple4 {
+        self.p1
+    }
+
+    pub fn p2(&self) -> Tuple4 {
+        self.p2
+    }
+
+    pub fn p3(&self) -> Tuple4 {
+        self.p3
+    }
+
+    /// The edge from `p1` to `p2`.
+    pub fn e1(&self) -> Tuple4 {
+        self.e1
+    }
+
+    /// The edge from `p1` to `p3`.
+    pub fn e2(&self) -> Tuple4 {
+        self.e2
+    }
+
+    /// The unit face normal, `e2 × e1`.
+    pub fn normal(&self) -> Tuple4 {
+        self.normal
+    }
+}
+
+impl Shape for Triangle {
+    fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    /// Möller–Trumbore: solves for the barycentric coordinates `u` and `v`
+    /// of the hit directly, without first intersecting the plane. Rays
+    /// parallel to the triangle, including those lying in it, miss. The
+    /// determinant grows with both edges and the direction, so it's judged
+    /// relative to their lengths.
+    fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+        let dir_cross_e2 = ray.direction.cross(self.e2);
+        let det = self.e1.dot(&dir_cross_e2);
+        let scale = self.e1.magnitude_squared()
+            * self.e2.magnitude_squared()
+            * ray.direction.magnitude_squared();
+        if det * det <= PARALLEL_EPSILON * scale {
+            return Vec::new();
+        }
+
+        let f = 1.0 / det;
+        let p1_to_origin = ray.origin - self.p1;
+        let u = f * p1_to_origin.dot(&dir_cross_e2);
+        if !(0.0..=1.0).contains(&u) {
+            return Vec::new();
+        }
+
+        let origin_cross_e1 = p1_to_origin.cross(self.e1);
+        let v = f * ray.direction.dot(&origin_cross_e1);
+        if v < 0.0 || u + v > 1.0 {
+            return Vec::new();
+        }
+
+        let t = f * self.e2.dot(&origin_cross_e1);
+        vec![Intersection::new(t, self)]
+    }
+
+    fn local_normal_at(&self, _: Tuple4) -> Tuple4 {
+        self.normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+    use crate::tuple::Elem;
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Tuple4::point(0.0, 1.0, 0.0),
+            Tuple4::point(-1.0, 0.0, 0.0),
+            Tuple4::point(1.0, 0.0, 0.0),
+        )
+    }
+
+    fn ray(origin: (Elem, Elem, Elem), direction: (Elem, Elem, Elem)) -> Ray {
+        Ray::new(
+            Tuple4::point(origin.0, origin.1, origin.2),
+            Tuple4::vector(direction.0, direction.1, direction.2),
+        )
+    }
+
+    #[test]
+    fn test_constructing_a_triangle() {
+        let t = triangle();
+
+        assert_eq!(t.p1(), Tuple4::point(0.0, 1.0, 0.0));
+        assert_eq!(t.p2(), Tuple4::point(-1.0, 0.0, 0.0));
+        assert_eq!(t.p3(), Tuple4::point(1.0, 0.0, 0.0));
+        assert_eq!(t.e1(), Tuple4::vector(-1.0, -1.0, 0.0));
+        assert_eq!(t.e2(), Tuple4::vector(1.0, -1.0, 0.0));
+        assert_eq!(t.normal(), Tuple4::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn test_finding_the_normal_on_a_triangle() {
+        let t = triangle();
+
+        for p in [
+            Tuple4::point(0.0, 0.5, 0.0),
+            Tuple4::point(-0.5, 0.75, 0.0),
+            Tuple4::point(0.5, 0.25, 0.0),
+        ] {
+            assert_eq!(t.local_normal_at(p), t.normal());
+        }
+    }
+
+    #[test]
+    fn test_intersecting_a_ray_parallel_to_the_triangle() {
+        let t = triangle();
+
+        let xs = t.local_intersect(&ray((0.0, -1.0, -2.0), (0.0, 1.0, 0.0)));
+
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn test_a_ray_misses_each_edge_of_the_triangle() {
+        let t = triangle();
+        let origins = [
+            // Past the p1-p3 edge.
+            (1.0, 1.0, -2.0),
+            // Past the p1-p2 edge.
+            (-1.0, 1.0, -2.0),
+            // Past the p2-p3 edge.
+            (0.0, -1.0, -2.0),
+        ];
+
+        for origin in origins {
+            let xs = t.local_intersect(&ray(origin, (0.0, 0.0, 1.0)));
+
+            assert!(xs.is_empty(), "ray from {:?}", origin);
+        }
+    }
+
+    #[test]
+    fn test_a_ray_strikes_a_triangle() {
+        let t = triangle();
+
+        let xs = t.local_intersect(&ray((0.0, 0.5, -2.0), (0.0, 0.0, 1.0)));
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 2.0);
+        assert!(ptr::addr_eq(xs[0].object, &t));
+    }
+
+    #[test]
+    fn test_a_ray_strikes_a_tiny_triangle() {
+        let t = Triangle::new(
+            Tuple4::point(0.0, 1e-3, 0.0),
+            Tuple4::point(-1e-3, 0.0, 0.0),
+            Tuple4::point(1e-3, 0.0, 0.0),
+        );
+
+        let xs = t.local_intersect(&ray((0.0, 5e-4, -2.0), (0.0, 0.0, 1.0)));
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 2.0);
+    }
+}
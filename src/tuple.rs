@@ -1,8 +1,29 @@
-use std::ops::{Add, Div, Mul, Sub};
-
-type Elem = f64;
-
-#[derive(Debug, PartialEq, Clone, Copy)]
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::{Add, Div, Index, Mul, Neg, Sub};
+
+use crate::approx::{ApproxEq, DEFAULT_EPSILON};
+
+/// The scalar used by tuples, matrices and everything built on them;
+/// `f32` with the `f32` feature, `f64` otherwise.
+#[cfg(not(feature = "f32"))]
+pub type Elem = f64;
+#[cfg(feature = "f32")]
+pub type Elem = f32;
+
+#[cfg(feature = "f32")]
+pub use std::f32::consts;
+/// Mathematical constants for [`Elem`].
+#[cfg(not(feature = "f32"))]
+pub use std::f64::consts;
+
+/// With the `serde` feature, serialized as `[x, y, z, w]`.
+#[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[Elem; 4]", into = "[Elem; 4]")
+)]
 pub struct Tuple4 {
     pub x: Elem,
     pub y: Elem,
@@ -28,12 +49,16 @@ impl Tuple4 {
         Tuple4::new(x.into(), y.into(), z.into(), 0.0)
     }
 
+    /// Whether `w` is 1, give or take `DEFAULT_EPSILON` of drift from
+    /// transforms.
     pub fn is_point(&self) -> bool {
-        self.w == 1.0
+        (self.w - 1.0).abs() < DEFAULT_EPSILON
     }
 
+    /// Whether `w` is 0, give or take `DEFAULT_EPSILON` of drift from
+    /// transforms.
     pub fn is_vector(&self) -> bool {
-        self.w == 0.0
+        self.w.abs() < DEFAULT_EPSILON
     }
 
     pub fn negate(self) -> Self {
@@ -41,11 +66,21 @@ impl Tuple4 {
     }
 
     pub fn magnitude(&self) -> Elem {
-        self.dot(self).sqrt()
+        self.magnitude_squared().sqrt()
     }
 
+    /// Squared length, for comparing lengths without the square root.
+    pub fn magnitude_squared(&self) -> Elem {
+        self.dot(self)
+    }
+
+    /// Unit-length copy of `self`. A zero-length tuple has no direction and
+    /// comes back unchanged rather than full of NaNs.
     pub fn normalize(self) -> Self {
         let mag = self.magnitude();
+        if mag == 0.0 {
+            return self;
+        }
         self / mag
     }
 
@@ -53,7 +88,12 @@ impl Tuple4 {
         self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
     }
 
+    /// Cross product of two vectors; `w` takes no part in it.
     pub fn cross(self, other: Tuple4) -> Self {
+        debug_assert!(
+            self.is_vector() && other.is_vector(),
+            "cross product is only defined for vectors"
+        );
         Self::vector(
             self.y * other.z - self.z * other.y,
             self.z * other.x - self.x * other.z,
@@ -61,9 +101,105 @@ impl Tuple4 {
         )
     }
 
+    /// Mirrors `self` about `normal`, which must be a unit vector.
     pub fn reflect(self, normal: Tuple4) -> Self {
+        debug_assert!(
+            (normal.magnitude_squared() - 1.0).abs() < DEFAULT_EPSILON,
+            "reflect needs a unit normal"
+        );
         self - normal * 2.0 * self.dot(&normal)
     }
+
+    /// `self + (other - self) * t`; `t` outside `0..=1` extrapolates.
+    pub fn lerp(self, other: Tuple4, t: Elem) -> Self {
+        self + (other - self) * t
+    }
+
+    /// Clamps every component, `w` included, into `min..=max`.
+    pub fn clamp(self, min: Elem, max: Elem) -> Self {
+        self.map(|a| a.clamp(min, max))
+    }
+
+    pub fn min_components(self, other: Tuple4) -> Self {
+        self.zip_with(other, Elem::min)
+    }
+
+    pub fn max_components(self, other: Tuple4) -> Self {
+        self.zip_with(other, Elem::max)
+    }
+
+    fn map(self, f: impl Fn(Elem) -> Elem) -> Self {
+        Tuple4::from(self.to_array().map(f))
+    }
+
+    fn zip_with(self, other: Tuple4, f: impl Fn(Elem, Elem) -> Elem) -> Self {
+        let (a, b) = (self.to_array(), other.to_array());
+        Tuple4::from(std::array::from_fn(|i| f(a[i], b[i])))
+    }
+
+    pub fn to_array(&self) -> [Elem; 4] {
+        [self.x, self.y, self.z, self.w]
+    }
+
+    /// The components in `x, y, z, w` order.
+    pub fn iter(&self) -> std::array::IntoIter<Elem, 4> {
+        self.to_array().into_iter()
+    }
+}
+
+/// Indexes 0 to 3 map to `x`, `y`, `z` and `w`.
+impl Index<usize> for Tuple4 {
+    type Output = Elem;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        match i {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            3 => &self.w,
+            _ => panic!("Index {} out of bounds for Tuple4", i),
+        }
+    }
+}
+
+impl From<[Elem; 4]> for Tuple4 {
+    fn from([x, y, z, w]: [Elem; 4]) -> Self {
+        Tuple4 { x, y, z, w }
+    }
+}
+
+impl From<Tuple4> for [Elem; 4] {
+    fn from(t: Tuple4) -> Self {
+        t.to_array()
+    }
+}
+
+impl ApproxEq for Tuple4 {
+    fn approx_elements(&self) -> Cow<'_, [Elem]> {
+        Cow::Owned(self.to_array().to_vec())
+    }
+}
+
+// Matches `w` exactly rather than through `is_point`/`is_vector`, so a
+// drifted `w` shows up in the output instead of being hidden.
+impl fmt::Debug for Tuple4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = if self.w == 1.0 {
+            "point"
+        } else if self.w == 0.0 {
+            "vector"
+        } else {
+            "tuple"
+        };
+
+        let mut t = f.debug_tuple(name);
+        t.field(&self.x).field(&self.y).field(&self.z);
+        if name == "tuple" {
+            t.field(&self.w);
+        }
+
+        t.finish()
+    }
 }
 
 impl Add for Tuple4 {
@@ -121,13 +257,67 @@ impl Div<Elem> for Tuple4 {
     }
 }
 
+impl Neg for Tuple4 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.negate()
+    }
+}
+
+impl Neg for &Tuple4 {
+    type Output = Tuple4;
+
+    fn neg(self) -> Self::Output {
+        self.negate()
+    }
+}
+
+/// Borrowed-operand versions of a binary operator, copying through to the
+/// owned impl.
+macro_rules! forward_ref_binop {
+    (impl $imp:ident<$rhs:ty>, $method:ident for $lhs:ty) => {
+        impl $imp<&$rhs> for &$lhs {
+            type Output = <$lhs as $imp<$rhs>>::Output;
+
+            fn $method(self, other: &$rhs) -> Self::Output {
+                $imp::$method(*self, *other)
+            }
+        }
+
+        impl $imp<$rhs> for &$lhs {
+            type Output = <$lhs as $imp<$rhs>>::Output;
+
+            fn $method(self, other: $rhs) -> Self::Output {
+                $imp::$method(*self, other)
+            }
+        }
+
+        impl $imp<&$rhs> for $lhs {
+            type Output = <$lhs as $imp<$rhs>>::Output;
+
+            fn $method(self, other: &$rhs) -> Self::Output {
+                $imp::$method(self, *other)
+            }
+        }
+    };
+}
+
+forward_ref_binop!(impl Add<Tuple4>, add for Tuple4);
+forward_ref_binop!(impl Sub<Tuple4>, sub for Tuple4);
+forward_ref_binop!(impl Mul<Elem>, mul for Tuple4);
+forward_ref_binop!(impl Mul<Tuple4>, mul for Elem);
+forward_ref_binop!(impl Div<Elem>, div for Tuple4);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::approx::tolerance;
+    use crate::assert_approx_eq;
 
-    const EPSILON: f64 = 1e-6;
+    const EPSILON: Elem = 1e-6;
 
-    fn equal(a: f64, b: f64) -> bool {
+    fn equal(a: Elem, b: Elem) -> bool {
         (a - b).abs() < EPSILON
     }
 
@@ -139,8 +329,8 @@ mod tests {
         assert_eq!(tuple.y, -4.2);
         assert_eq!(tuple.z, 3.1);
         assert_eq!(tuple.w, 1.0);
-        assert_eq!(tuple.is_point(), true);
-        assert_eq!(tuple.is_vector(), false);
+        assert!(tuple.is_point());
+        assert!(!tuple.is_vector());
     }
 
     #[test]
@@ -151,8 +341,8 @@ mod tests {
         assert_eq!(tuple.y, -4.2);
         assert_eq!(tuple.z, 3.1);
         assert_eq!(tuple.w, 0.0);
-        assert_eq!(tuple.is_point(), false);
-        assert_eq!(tuple.is_vector(), true);
+        assert!(!tuple.is_point());
+        assert!(tuple.is_vector());
     }
 
     #[test]
@@ -170,23 +360,85 @@ mod tests {
     }
 
     #[test]
-    fn test_adding_two_tuples() {
-        let t1 = Tuple4::new(3.0, -2.0, 5.0, 1.0);
-        let t2 = Tuple4::new(-2.0, 3.0, 1.0, 0.0);
+    fn test_debug_output_names_the_kind_of_tuple() {
+        let point = Tuple4::point(1.0, -2.5, 3.0);
+        let vector = Tuple4::vector(0.0, 1.0, 0.0);
+        let tuple = Tuple4::new(1.0, 2.0, 3.0, 4.0);
+
+        assert_eq!(format!("{:?}", point), "point(1.0, -2.5, 3.0)");
+        assert_eq!(format!("{:?}", vector), "vector(0.0, 1.0, 0.0)");
+        assert_eq!(format!("{:?}", tuple), "tuple(1.0, 2.0, 3.0, 4.0)");
+    }
+
+    #[test]
+    fn test_debug_output_shows_a_drifted_w() {
+        let point = Tuple4::new(1.0, 2.0, 3.0, 1.0 + 5e-7);
+        let vector = Tuple4::new(1.0, 2.0, 3.0, -5e-7);
+
+        assert!(point.is_point() && vector.is_vector());
+        assert_eq!(
+            format!("{:?}", point),
+            format!("tuple(1.0, 2.0, 3.0, {:?})", point.w)
+        );
+        assert_eq!(
+            format!("{:?}", vector),
+            format!("tuple(1.0, 2.0, 3.0, {:?})", vector.w)
+        );
+    }
+
+    #[test]
+    fn test_drifted_w_still_counts() {
+        let point = Tuple4::new(1.0, 2.0, 3.0, 1.0 + 1e-9);
+        let vector = Tuple4::new(1.0, 2.0, 3.0, -1e-9);
+
+        assert!(point.is_point());
+        assert!(vector.is_vector());
+        assert!(!Tuple4::new(1.0, 2.0, 3.0, 0.5).is_point());
+        assert!(!Tuple4::new(1.0, 2.0, 3.0, 0.5).is_vector());
+    }
+
+    #[test]
+    fn test_adding_a_vector_to_a_point() {
+        let p = Tuple4::point(3.0, -2.0, 5.0);
+        let v = Tuple4::vector(-2.0, 3.0, 1.0);
+
+        let result = p + v;
+
+        assert_eq!(result, Tuple4::point(1.0, 1.0, 6.0));
+        assert!(result.is_point());
+    }
+
+    #[test]
+    fn test_subtracting_a_vector_from_a_point() {
+        let p = Tuple4::point(3.0, -2.0, 5.0);
+        let v = Tuple4::vector(-2.0, 3.0, 1.0);
+
+        let result = p - v;
+
+        assert_eq!(result, Tuple4::point(5.0, -5.0, 4.0));
+        assert!(result.is_point());
+    }
+
+    #[test]
+    fn test_subtracting_two_points() {
+        let p1 = Tuple4::point(3.0, 2.0, 1.0);
+        let p2 = Tuple4::point(5.0, 6.0, 7.0);
 
-        let result = t1 + t2;
+        let result = p1 - p2;
 
-        assert_eq!(result, Tuple4::new(1.0, 1.0, 6.0, 1.0));
+        assert_eq!(result, Tuple4::vector(-2.0, -4.0, -6.0));
+        assert!(result.is_vector());
     }
 
     #[test]
-    fn test_subtracting_two_tuples() {
-        let t1 = Tuple4::new(3.0, -2.0, 5.0, 1.0);
-        let t2 = Tuple4::new(-2.0, 3.0, 1.0, 0.0);
+    fn test_subtracting_two_vectors() {
+        let v1 = Tuple4::vector(3.0, 2.0, 1.0);
+        let v2 = Tuple4::vector(5.0, 6.0, 7.0);
 
-        let result = t1 - t2;
+        let result = v1 - v2;
 
-        assert_eq!(result, Tuple4::new(5.0, -5.0, 4.0, 1.0));
+        assert_eq!(result, Tuple4::vector(-2.0, -4.0, -6.0));
+        assert!(result.is_vector());
     }
 
     #[test]
@@ -196,6 +448,32 @@ mod tests {
         let result = t.negate();
 
         assert_eq!(result, Tuple4::new(-1.0, 2.0, -3.0, 4.0));
+        assert_eq!(-t, result);
+        assert_eq!(-&t, result);
+    }
+
+    #[test]
+    fn test_multiply_tuple_by_a_fraction_and_a_whole_number() {
+        let t = Tuple4::new(1.0, -2.0, 3.0, -4.0);
+
+        assert_eq!(t * 3.5, Tuple4::new(3.5, -7.0, 10.5, -14.0));
+        assert_eq!(t * 0.5, Tuple4::new(0.5, -1.0, 1.5, -2.0));
+    }
+
+    #[test]
+    #[allow(clippy::op_ref)]
+    fn test_operators_on_borrowed_tuples() {
+        let p = Tuple4::point(3.0, -2.0, 5.0);
+        let v = Tuple4::vector(-2.0, 3.0, 1.0);
+
+        assert_eq!(&p + &v, p + v);
+        assert_eq!(&p + v, p + v);
+        assert_eq!(p + &v, p + v);
+        assert_eq!(&p - &v, p - v);
+        assert_eq!(&v * 3.5, v * 3.5);
+        assert_eq!(v * &3.5, v * 3.5);
+        assert_eq!(3.5 * &v, v * 3.5);
+        assert_eq!(&v / 2.0, v / 2.0);
     }
 
     #[test]
@@ -228,13 +506,36 @@ mod tests {
         assert_eq!(result, Tuple4::new(0.5, -1.0, 1.5, -2.0));
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_round_trip_is_exact() {
+        let t = Tuple4::new(0.1, -1.0 / 3.0, consts::PI, 1e-30);
+
+        let json = serde_json::to_string(&t).unwrap();
+        let back: Tuple4 = serde_json::from_str(&json).unwrap();
+
+        assert!(json.starts_with('['));
+        assert_eq!(
+            <[Elem; 4]>::from(back).map(Elem::to_bits),
+            [t.x, t.y, t.z, t.w].map(Elem::to_bits)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_rejects_wrong_length() {
+        let err = serde_json::from_str::<Tuple4>("[1.0, 2.0, 3.0]").unwrap_err();
+
+        assert!(err.to_string().contains("invalid length 3"), "{}", err);
+    }
+
     #[test]
     fn test_vector_magnitude() {
         let v = Tuple4::vector(1.0, 2.0, 3.0);
 
         let mag = v.magnitude();
 
-        assert_eq!(mag * mag, 14.0);
+        assert!((mag * mag - 14.0).abs() <= tolerance(0.0));
     }
 
     #[test]
@@ -243,9 +544,41 @@ mod tests {
 
         let normalized_v = v.normalize();
 
-        assert_eq!(equal(normalized_v.x, 0.267261), true);
-        assert_eq!(equal(normalized_v.y, 0.534522), true);
-        assert_eq!(equal(normalized_v.z, 0.801783), true);
+        assert!(equal(normalized_v.x, 0.267261));
+        assert!(equal(normalized_v.y, 0.534522));
+        assert!(equal(normalized_v.z, 0.801783));
+    }
+
+    #[test]
+    fn test_normalizing_an_axis_vector() {
+        let v = Tuple4::vector(4.0, 0.0, 0.0);
+
+        assert_eq!(v.normalize(), Tuple4::vector(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_magnitude_of_normalized_vectors_is_one() {
+        for v in [
+            Tuple4::vector(1.0, 2.0, 3.0),
+            Tuple4::vector(-0.001, 0.0, 0.002),
+            Tuple4::vector(1e6, -3e5, 7e4),
+        ] {
+            assert!((v.normalize().magnitude() - 1.0).abs() <= tolerance(1e-12));
+        }
+    }
+
+    #[test]
+    fn test_magnitude_squared() {
+        let v = Tuple4::vector(1.0, 2.0, 3.0);
+
+        assert_eq!(v.magnitude_squared(), 14.0);
+    }
+
+    #[test]
+    fn test_normalizing_the_zero_vector_returns_it() {
+        let v = Tuple4::vector(0.0, 0.0, 0.0);
+
+        assert_eq!(v.normalize(), v);
     }
 
     #[test]
@@ -266,6 +599,71 @@ mod tests {
         let result = v1.cross(v2);
 
         assert_eq!(result, Tuple4::vector(-1.0, 2.0, -1.0));
+        assert_eq!(v2.cross(v1), Tuple4::vector(1.0, -2.0, 1.0));
+    }
+
+    #[test]
+    fn test_dot_product_includes_w() {
+        let t1 = Tuple4::new(1.0, 2.0, 3.0, 4.0);
+        let t2 = Tuple4::new(2.0, 3.0, 4.0, 5.0);
+
+        assert_eq!(t1.dot(&t2), 40.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cross product is only defined for vectors")]
+    #[cfg(debug_assertions)]
+    fn test_cross_product_of_a_point_panics() {
+        Tuple4::point(1.0, 2.0, 3.0).cross(Tuple4::vector(2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = Tuple4::point(0.0, 2.0, -4.0);
+        let b = Tuple4::point(2.0, 6.0, 4.0);
+
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), Tuple4::point(1.0, 4.0, 0.0));
+        assert_eq!(a.lerp(b, 2.0), Tuple4::point(4.0, 10.0, 12.0));
+    }
+
+    #[test]
+    fn test_clamp() {
+        let t = Tuple4::new(-0.5, 0.25, 1.5, 1.0);
+
+        assert_eq!(t.clamp(0.0, 1.0), Tuple4::new(0.0, 0.25, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_componentwise_min_and_max() {
+        let a = Tuple4::point(1.0, -2.0, 3.0);
+        let b = Tuple4::point(-1.0, 2.0, 3.5);
+
+        assert_eq!(a.min_components(b), Tuple4::point(-1.0, -2.0, 3.0));
+        assert_eq!(a.max_components(b), Tuple4::point(1.0, 2.0, 3.5));
+    }
+
+    #[test]
+    fn test_indexing_matches_fields() {
+        let t = Tuple4::new(4.3, -4.2, 3.1, 1.0);
+
+        assert_eq!([t[0], t[1], t[2], t[3]], [t.x, t.y, t.z, t.w]);
+        assert_eq!(t.iter().collect::<Vec<_>>(), vec![4.3, -4.2, 3.1, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 4 out of bounds for Tuple4")]
+    fn test_indexing_out_of_bounds_panics() {
+        let _ = Tuple4::point(1.0, 2.0, 3.0)[4];
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        let t = Tuple4::vector(1.5, -2.0, 0.25);
+
+        assert_eq!(Tuple4::from(t.to_array()), t);
+        assert_eq!(<[Elem; 4]>::from(t), t.to_array());
     }
 
     #[test]
@@ -281,12 +679,17 @@ mod tests {
     #[test]
     fn test_reflecting_vector_off_slanted_surface() {
         let v = Tuple4::vector(0.0, -1.0, 0.0);
-        let n = Tuple4::vector(2.0_f64.sqrt() / 2.0, 2.0_f64.sqrt() / 2.0, 0.0);
+        let n = Tuple4::vector(Elem::sqrt(2.0) / 2.0, Elem::sqrt(2.0) / 2.0, 0.0);
 
         let r = v.reflect(n);
 
-        assert!(equal(r.x, 1.0));
-        assert!(equal(r.y, 0.0));
-        assert_eq!(r.z, 0.0);
+        assert_approx_eq!(r, Tuple4::vector(1.0, 0.0, 0.0), tolerance(1e-9));
+    }
+
+    #[test]
+    #[should_panic(expected = "reflect needs a unit normal")]
+    #[cfg(debug_assertions)]
+    fn test_reflecting_off_a_non_unit_normal_panics() {
+        Tuple4::vector(1.0, -1.0, 0.0).reflect(Tuple4::vector(0.0, 2.0, 0.0));
     }
 }